@@ -1,12 +1,25 @@
-// #[cfg(any())] // NOTE: Temporarily disabling this example due to editor crash on hot reload
 mod plugin;
 
-use std::sync::{
-    atomic::{AtomicUsize, Ordering::Relaxed},
-    Arc,
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering::Relaxed},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
 };
 
-use gdext_egui::{egui, EguiBridge, ViewportBuilder, ViewportId};
+use gdext_egui::{
+    egui,
+    widgets::{
+        ConsoleArgs, MenuItemOptions, ObjectInspector, PerformanceMonitor, SceneTreeInspector,
+        VariantEdit,
+    },
+    EguiBridge, PanelGroup, ViewportBuilder, ViewportId,
+};
 use godot::{
     classes::{self, CanvasLayer, ICanvasLayer},
     prelude::*,
@@ -14,7 +27,12 @@ use godot::{
 struct MyExtension;
 
 #[gdextension]
-unsafe impl ExtensionLibrary for MyExtension {}
+unsafe impl ExtensionLibrary for MyExtension {
+    fn on_level_deinit(level: InitLevel) {
+        // Release egui resources before the library is unloaded; e.g. hot reload.
+        gdext_egui::on_level_deinit(level);
+    }
+}
 
 /* ---------------------------------------------------------------------------------------------- */
 /*                                            SHOWCASE                                            */
@@ -26,11 +44,8 @@ struct Showcase {
     base: Base<Node>,
 
     /// Once this node is initiated, the `ready()` routine automatically initialize
-    /// required context for EGUI rendering. Usually adding this multiple times should not
-    /// be trouble, as long as you don't use immediate viewport rendering.
-    ///
-    /// If you want to call [`egui::Context::show_viewport_immediate`] method in any way,
-    /// you should not create more than single [`EguiBridge`] in the scene tree.
+    /// required context for EGUI rendering. Adding this multiple times is fine; see
+    /// [`MultiBridgeTest`].
     #[init(default = OnReady::manual())]
     egui: OnReady<Gd<EguiBridge>>,
 
@@ -46,6 +61,10 @@ impl INode for Showcase {
         let mut gd_self = self.to_gd();
         gd_self.add_child(&self.egui.clone());
         self.egui.set_owner(&gd_self);
+
+        // Keep drawing while the game is paused; the bridge inherits the process mode by
+        // default. See `PausePolicy`.
+        gd_self.set_process_mode(classes::node::ProcessMode::ALWAYS);
     }
 
     fn process(&mut self, _d: f64) {
@@ -53,6 +72,18 @@ impl INode for Showcase {
         // `EguiBridge::current_frame()` method!
         let ctx = self.egui.bind().current_frame().clone();
         self.demos.ui(&ctx);
+
+        let Some(mut tree) = self.base().get_tree() else {
+            return;
+        };
+
+        egui::Window::new("Pause Menu").show(&ctx, |ui| {
+            let mut paused = tree.is_paused();
+
+            if ui.checkbox(&mut paused, "Pause game").changed() {
+                tree.set_pause(paused);
+            }
+        });
     }
 }
 
@@ -68,6 +99,9 @@ struct WidgetExample {
     /// This should be set from editor
     #[init(default = OnReady::manual())]
     egui: OnReady<Gd<EguiBridge>>,
+
+    /// Dialog being awaited; polled manually every frame. See `process`.
+    pending_dialog: Option<Pin<Box<dyn Future<Output = bool>>>>,
 }
 
 #[godot_api]
@@ -75,12 +109,62 @@ impl INode for WidgetExample {
     fn process(&mut self, _d: f64) {
         // To start new frame ... Registered widgets won't be updated unless you call
         // this. (at least once in a frame)
-        self.egui.bind().current_frame();
+        let ctx = self.egui.bind().current_frame().clone();
+
+        /* ------------------------------------- Async Dialogs ---------------------------------- */
+
+        egui::Window::new("Dialogs").show(&ctx, |ui| {
+            let waiting = self.pending_dialog.is_some();
+
+            if ui
+                .add_enabled(!waiting, egui::Button::new("Delete save"))
+                .clicked()
+            {
+                let dialog = self.egui.bind().confirm("Delete save?");
+                self.pending_dialog = Some(Box::pin(dialog));
+            }
+        });
+
+        // `godot::task::spawn` isn't available in the gdext revision this crate is pinned
+        // to; it was added in gdext 0.3. Once upgraded, the dialog can be awaited directly,
+        // without `pending_dialog` and the manual polling below:
+        //
+        // ```
+        // let dialog = self.egui.bind().confirm("Delete save?");
+        // godot::task::spawn(async move {
+        //     godot_print!("Delete save confirmed: {}", dialog.await);
+        // });
+        // ```
+        //
+        // Until then, any executor works. Here it's polled every frame, so the waker has
+        // nothing to do.
+        if let Some(dialog) = self.pending_dialog.as_mut() {
+            struct NoopWake;
+
+            impl Wake for NoopWake {
+                fn wake(self: Arc<Self>) {}
+            }
+
+            let waker = Waker::from(Arc::new(NoopWake));
+
+            if let Poll::Ready(confirmed) = dialog.as_mut().poll(&mut Context::from_waker(&waker)) {
+                godot_print!("Delete save confirmed: {confirmed}");
+                self.pending_dialog = None;
+            }
+        }
     }
 
     fn ready(&mut self) {
         self.egui.init(EguiBridge::new_alloc());
 
+        // Persisted files are loaded when the bridge enters the tree; set paths before
+        // adding it. Persistence is opt-in.
+        {
+            let mut egui = self.egui.bind_mut();
+            egui.dock_layout_path = "example_dock_layout.ron".into();
+            egui.input_overrides_path = "example_input_overrides.cfg".into();
+        }
+
         let mut gd_self = self.to_gd();
         gd_self.add_child(&self.egui.clone());
         self.egui.set_owner(&gd_self);
@@ -113,6 +197,206 @@ impl INode for WidgetExample {
                 }
             },
         );
+
+        /* ------------------------------------------ Panels -------------------------------------- */
+
+        // Predefined panels around the screen. Empty space between panels passes input
+        // through to the game.
+        egui.panel_item_push_back(PanelGroup::Left, |ui: &mut egui::Ui| {
+            ui.heading("Left Panel");
+            ui.label("Resizable; drag the edge.");
+        });
+
+        egui.panel_item_push_back(PanelGroup::Right, {
+            let mut value = 0.5;
+            move |ui: &mut egui::Ui| {
+                ui.heading("Right Panel");
+                ui.add(egui::Slider::new(&mut value, 0.0..=1.0));
+            }
+        });
+
+        egui.panel_item_push_back(PanelGroup::BottomLeft, |ui: &mut egui::Ui| {
+            ui.label("Bottom Left");
+        });
+
+        egui.panel_item_push_back(PanelGroup::BottomRight, |ui: &mut egui::Ui| {
+            ui.label("Bottom Right");
+        });
+
+        /* ---------------------------------------- Menu Bar ------------------------------------- */
+
+        // Any system can register menu items by path. Empty part of the bar doesn't steal
+        // input from the game.
+        egui.menu_action_insert(
+            ["File", "Quit"],
+            MenuItemOptions::default()
+                .order(100)
+                .separator(true)
+                .shortcut(egui::KeyboardShortcut::new(
+                    egui::Modifiers::COMMAND,
+                    egui::Key::Q,
+                )),
+            {
+                let gd_self = gd_self.clone();
+                move || {
+                    if let Some(mut tree) = gd_self.get_tree() {
+                        tree.quit();
+                    }
+                }
+            },
+        );
+
+        egui.menu_item_insert(
+            ["Help", "About"],
+            MenuItemOptions::default(),
+            |ui: &mut egui::Ui| {
+                ui.label("gdext-egui example");
+            },
+        );
+
+        /* ------------------------------------------ Dock ------------------------------------------ */
+
+        // Dock panels can be dragged into tabs and splits; the layout is restored on next
+        // run. `View > Reset Dock Layout` puts them back in a single tab group.
+        egui.dock_panel_register("example/stats", "Stats", |ui: &mut egui::Ui| {
+            ui.label(format!(
+                "FPS: {}",
+                classes::Engine::singleton().get_frames_per_second()
+            ));
+        });
+
+        egui.dock_panel_register("example/notes", "Notes", {
+            let mut text = String::new();
+            move |ui: &mut egui::Ui| {
+                ui.text_edit_multiline(&mut text);
+            }
+        });
+
+        /* --------------------------------------- Scene Tree -------------------------------------- */
+
+        // Selecting a node in the scene tree inspects its properties.
+        let properties = Rc::new(RefCell::new(ObjectInspector::default()));
+
+        let inspector = SceneTreeInspector::new().on_select({
+            let properties = properties.clone();
+            move |node: Gd<Node>| {
+                properties.borrow_mut().set_object(Some(node.upcast()));
+            }
+        });
+
+        egui.register_render_callback_last(0, inspector.into_window("Scene Tree"))
+            .detach();
+
+        egui.register_render_callback_last(0, move |ctx: &egui::Context| {
+            egui::Window::new("Properties").show(ctx, |ui| {
+                properties.borrow_mut().show(ui);
+            });
+        })
+        .detach();
+
+        // Any variant can be edited in place; containers are collapsible and paginated.
+        egui.register_render_callback_last(0, {
+            let mut value = dict! {
+                "name": "player",
+                "position": Vector3::new(1., 2., 3.),
+                "color": Color::from_rgb(1., 0.5, 0.),
+                "scores": PackedInt32Array::from(&(0..100).collect::<Vec<_>>()[..]),
+            }
+            .to_variant();
+
+            move |ctx: &egui::Context| {
+                egui::Window::new("Variant").show(ctx, |ui| {
+                    if ui.add(VariantEdit::new(&mut value)).changed() {
+                        godot_print!("Variant changed: {value}");
+                    }
+                });
+            }
+        })
+        .detach();
+
+        /* -------------------------------------- Performance ------------------------------------- */
+
+        // Toggled with `toggle_performance` action, if it's defined in the input map.
+        egui.register_render_callback_last(
+            0,
+            PerformanceMonitor::new()
+                .toggle_action("toggle_performance")
+                .into_overlay(),
+        )
+        .detach();
+
+        /* ---------------------------------------- Console --------------------------------------- */
+
+        // GDScript may register commands too: `bridge.console_command_register_callable(...)`.
+        egui.console_command_register("echo", "Prints given arguments", |args: &ConsoleArgs| {
+            Ok(args.raw().join(" "))
+        });
+
+        egui.console_command_register(
+            "timescale",
+            "Sets Engine.time_scale",
+            |args: &ConsoleArgs| {
+                let scale = args.get::<f64>(0)?;
+                classes::Engine::singleton().set_time_scale(scale);
+                Ok(format!("Time scale: {scale}"))
+            },
+        );
+
+        egui.register_render_callback_last(0, egui.console().into_window("Console"))
+            .detach();
+
+        /* ------------------------------------ Input Rebinding ----------------------------------- */
+
+        // Changed bindings are saved to `EguiBridge::input_overrides_path` set in `ready`, and
+        // reapplied on next launch.
+        egui.register_render_callback_last(0, egui.input_rebinder().into_window("Controls"))
+            .detach();
+
+        // Keyed callback replaces the previous one with same key, so reloading this scene
+        // doesn't duplicate the panel. Detached callbacks live until they're replaced.
+        egui.register_keyed_render_callback_last(
+            "WidgetExample/Debug",
+            0,
+            |ctx: &egui::Context| {
+                egui::Window::new("Debug").show(ctx, |ui| {
+                    ui.label(format!("Frame: {}", ctx.frame_nr()));
+                });
+            },
+        )
+        .detach();
+
+        /* ------------------------------------ Worker Threads ---------------------------------- */
+
+        // `EguiBridge` can't leave main thread; post works through a handle instead.
+        let handle = egui.handle();
+
+        std::thread::spawn(move || {
+            let progress = Arc::new(AtomicUsize::new(0));
+
+            handle.register_render_callback_last(0, {
+                let progress = progress.clone();
+                move |ctx: &egui::Context| {
+                    let value = progress.load(Relaxed);
+
+                    egui::Window::new("Worker Thread").show(ctx, |ui| {
+                        ui.add(egui::ProgressBar::new(value as f32 / 100.).show_percentage());
+                    });
+
+                    // Dispose the window once the work is done.
+                    value < 100
+                }
+            });
+
+            for i in 1..=100 {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                progress.store(i, Relaxed);
+
+                if !handle.request_repaint() {
+                    // Bridge is gone.
+                    break;
+                }
+            }
+        });
     }
 }
 
@@ -156,9 +440,12 @@ impl ICanvasLayer for ToolTest {
             return;
         };
 
-        // This should be called first!
+        // This should be called first! With `reactive` bridge, there's nothing to draw
+        // unless any input or repaint request is pending.
         let egui = egui.bind();
-        let ctx = egui.current_frame();
+        let Some(ctx) = egui.try_current_frame() else {
+            return;
+        };
 
         let time = classes::Time::singleton();
         let tick = time.get_ticks_usec() as f64 / 1e6;
@@ -205,3 +492,209 @@ impl ICanvasLayer for ToolTest {
         );
     }
 }
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                        MULTI BRIDGE TEST                                       */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Frames to run each phase of [`MultiBridgeTest`].
+const MULTI_BRIDGE_TEST_FRAMES: usize = 30;
+
+/// Integration test of two independent bridges, each showing a window and an immediate
+/// viewport. Run `tests/MultiBridgeTest.tscn` headless (see `README.md`); the process exits
+/// with non-zero code if any check fails.
+///
+/// - Render callbacks run only with the context of the bridge they're registered to.
+/// - Immediate viewports are rendered by the bridge which owns the context. Both bridges
+///   spawn viewports as embedded windows, so they're not drawn inline on headless runs.
+/// - [`gdext_egui::on_level_deinit`] releases every registered callback, and the bridges
+///   start over on the next frame.
+#[derive(GodotClass)]
+#[class(init, base=Node)]
+struct MultiBridgeTest {
+    base: Base<Node>,
+    bridges: Vec<TestedBridge>,
+    frames: usize,
+    failures: Vec<String>,
+}
+
+struct TestedBridge {
+    bridge: Gd<EguiBridge>,
+
+    /// Number of calls to the render callback, and the context of the last call.
+    callback_calls: Rc<Cell<usize>>,
+    callback_context: Rc<RefCell<Option<egui::Context>>>,
+
+    immediate_frames: usize,
+
+    /// Counters when the bridges were torn down.
+    teardown_snapshot: (usize, usize),
+}
+
+#[godot_api]
+impl INode for MultiBridgeTest {
+    fn ready(&mut self) {
+        for _ in 0..2 {
+            let mut bridge = EguiBridge::new_alloc();
+
+            // Otherwise egui draws immediate viewports inline, without asking the bridge.
+            bridge.bind_mut().embedded_subwindows = true;
+            self.base_mut().add_child(&bridge.clone());
+
+            let callback_calls = Rc::new(Cell::new(0));
+            let callback_context = Rc::new(RefCell::new(None));
+
+            bridge
+                .bind()
+                .register_render_callback_last(0, {
+                    let calls = callback_calls.clone();
+                    let context = callback_context.clone();
+
+                    move |ctx: &egui::Context| {
+                        calls.set(calls.get() + 1);
+                        *context.borrow_mut() = Some(ctx.clone());
+                    }
+                })
+                .detach();
+
+            self.bridges.push(TestedBridge {
+                bridge,
+                callback_calls,
+                callback_context,
+                immediate_frames: 0,
+                teardown_snapshot: (0, 0),
+            });
+        }
+    }
+
+    fn process(&mut self, _d: f64) {
+        self.frames += 1;
+
+        for index in 0..self.bridges.len() {
+            self.run_bridge(index);
+        }
+
+        if self.frames == MULTI_BRIDGE_TEST_FRAMES {
+            self.check(|x| x.callback_calls.get() > 0, "render callback never ran");
+            self.check(|x| x.immediate_frames > 0, "immediate viewport never ran");
+            self.check_surfaces();
+
+            // Same as what happens on hot reload.
+            gdext_egui::on_level_deinit(InitLevel::Scene);
+
+            for x in &mut self.bridges {
+                x.teardown_snapshot = (x.callback_calls.get(), x.immediate_frames);
+            }
+        }
+
+        if self.frames == MULTI_BRIDGE_TEST_FRAMES * 2 {
+            self.check(
+                |x| x.callback_calls.get() == x.teardown_snapshot.0,
+                "render callback survived teardown",
+            );
+            self.check(
+                |x| x.immediate_frames > x.teardown_snapshot.1,
+                "bridge didn't start over after teardown",
+            );
+
+            self.finish();
+        }
+    }
+}
+
+impl MultiBridgeTest {
+    fn run_bridge(&mut self, index: usize) {
+        let frames = self.frames;
+        let tested = &mut self.bridges[index];
+        let ctx = tested.bridge.bind().current_frame().clone();
+
+        // Callbacks are called at the end of the previous frame.
+        if let Some(seen) = tested.callback_context.borrow_mut().take() {
+            if seen != ctx {
+                self.failures.push(format!(
+                    "Bridge {index}: render callback got foreign context"
+                ));
+            }
+        }
+
+        egui::Window::new(format!("Bridge {index}")).show(&ctx, |ui| {
+            ui.label(format!("Frame: {frames}"));
+        });
+
+        let mut foreign = false;
+        let mut drawn = false;
+
+        ctx.show_viewport_immediate(
+            Self::immediate_id(index),
+            ViewportBuilder::default().with_title(format!("Immediate of bridge {index}")),
+            |vp_ctx, _| {
+                // Immediate viewport must be dispatched to the owner of the context.
+                foreign |= *vp_ctx != ctx;
+                drawn = true;
+
+                egui::CentralPanel::default().show(vp_ctx, |ui| {
+                    ui.heading(format!("Immediate viewport of bridge {index}"));
+                    ui.label(format!("Frame: {frames}"));
+                });
+            },
+        );
+
+        if drawn {
+            tested.immediate_frames += 1;
+        }
+
+        if foreign {
+            self.failures.push(format!(
+                "Bridge {index}: immediate viewport got foreign context"
+            ));
+        }
+    }
+
+    fn immediate_id(index: usize) -> ViewportId {
+        ViewportId::from_hash_of(("MultiBridgeTest", index))
+    }
+
+    /// Each bridge should hold a surface for its own immediate viewport, and none for the
+    /// viewport of the other bridge.
+    fn check_surfaces(&mut self) {
+        for (index, tested) in self.bridges.iter().enumerate() {
+            let bridge = tested.bridge.bind();
+
+            for other in 0..self.bridges.len() {
+                let expected = other == index;
+
+                if bridge.has_viewport(Self::immediate_id(other)) != expected {
+                    self.failures.push(format!(
+                        "Bridge {index}: surface of immediate viewport {other} {}",
+                        if expected { "missing" } else { "created" }
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check(&mut self, pass: impl Fn(&TestedBridge) -> bool, message: &str) {
+        for (index, tested) in self.bridges.iter().enumerate() {
+            if !pass(tested) {
+                self.failures.push(format!("Bridge {index}: {message}"));
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        for failure in &self.failures {
+            godot_error!("MultiBridgeTest: {failure}");
+        }
+
+        let code = if self.failures.is_empty() {
+            godot_print!("MultiBridgeTest: passed");
+            0
+        } else {
+            1
+        };
+
+        if let Some(mut tree) = self.base().get_tree() {
+            tree.quit_ex().exit_code(code).done();
+        }
+    }
+}
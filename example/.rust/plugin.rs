@@ -1,8 +1,18 @@
-use std::{cell::Cell, rc::Rc};
-
-use gdext_egui::{context::FnEguiDrawExt, egui, EguiBridge};
+use std::cell::Cell;
+
+use gdext_egui::{
+    context::WidgetHandle,
+    editor::{
+        EguiEditorBottomPanel, EguiEditorDock, EguiEditorOverlay, EguiInspectorPlugin,
+        InspectorTarget,
+    },
+    egui, EguiBridge,
+};
 use godot::{
-    classes::{EditorInterface, EditorPlugin, IEditorPlugin},
+    classes::{
+        editor_plugin::{AfterGuiInput, DockSlot},
+        Camera3D, Control, EditorInterface, EditorPlugin, IEditorPlugin, InputEvent,
+    },
     prelude::*,
 };
 
@@ -12,7 +22,10 @@ pub struct TestEguiPlugin {
     base: Base<EditorPlugin>,
     egui: Option<Gd<EguiBridge>>,
 
-    handle: Option<Rc<()>>,
+    inspector: Option<Gd<EguiInspectorPlugin>>,
+    overlay: Option<EguiEditorOverlay>,
+
+    handle: Option<WidgetHandle>,
     counter: Cell<usize>,
 }
 
@@ -29,15 +42,13 @@ impl IEditorPlugin for TestEguiPlugin {
             ctx.set_zoom_factor(1.5);
         });
 
-        let handle = Rc::new(());
+        // Callback is disposed along with the handle.
         let self_gd = self.to_gd();
-        egui.bind().register_render_callback_last(
-            0,
-            (move |ctx: &egui::Context| {
+        let handle = egui
+            .bind()
+            .register_render_callback_last(0, move |ctx: &egui::Context| {
                 self_gd.bind().show(ctx);
-            })
-            .bind(Rc::downgrade(&handle)),
-        );
+            });
         self.handle = Some(handle);
 
         let edt = EditorInterface::singleton();
@@ -46,16 +57,97 @@ impl IEditorPlugin for TestEguiPlugin {
 
         egui.bind().sync_root_region(Some(main_screen.upcast()));
         self.make_visible(false);
+
+        // Draw rotation of every 2D node with egui.
+        let mut inspector = EguiInspectorPlugin::new_gd();
+        inspector.bind_mut().register(
+            InspectorTarget::Property {
+                class: "Node2D".into(),
+                property: "rotation".into(),
+            },
+            |ui, value| {
+                let mut rad = value.to::<f32>();
+                ui.drag_angle(&mut rad);
+                *value = rad.to_variant();
+            },
+        );
+
+        self.base_mut().add_inspector_plugin(&inspector);
+        self.inspector = Some(inspector);
+
+        // Docks are removed along with this plugin; no need to keep them.
+        let dock = EguiEditorDock::new(DockSlot::RIGHT_UL, "Egui Dock", |ui| {
+            ui.heading("Egui Dock");
+            ui.label("Drawn by egui, inside the editor dock.");
+        });
+        EguiEditorDock::install(&dock, self.to_gd().upcast());
+
+        let mut count = 0;
+        let panel = EguiEditorBottomPanel::new("Egui Panel", move |ui| {
+            if ui.button("Click me").clicked() {
+                count += 1;
+            }
+
+            ui.label(format!("Clicked {count} times"));
+        });
+        EguiEditorBottomPanel::install(&panel, self.to_gd().upcast());
+
+        // Draw over 2D / 3D editor viewports.
+        self.overlay = Some(EguiEditorOverlay::new(|ctx| {
+            egui::Window::new("Overlay").show(ctx, |ui| {
+                ui.label("Drawn over the editor viewport");
+            });
+        }));
+
+        self.base_mut().set_input_event_forwarding_always_enabled();
+        self.base_mut().set_force_draw_over_forwarding_enabled();
     }
 
     fn exit_tree(&mut self) {
         let _ = self.handle.take();
 
+        self.overlay = None;
+
+        if let Some(inspector) = self.inspector.take() {
+            self.base_mut().remove_inspector_plugin(&inspector);
+        }
+
         if let Some(mut egui) = self.egui.take() {
             egui.queue_free();
         }
     }
 
+    fn forward_canvas_draw_over_viewport(&mut self, viewport_control: Gd<Control>) {
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.draw_over_viewport(viewport_control);
+        }
+    }
+
+    fn forward_canvas_gui_input(&mut self, event: Gd<InputEvent>) -> bool {
+        self.overlay
+            .as_mut()
+            .is_some_and(|x| x.forward_gui_input(event))
+    }
+
+    fn forward_3d_draw_over_viewport(&mut self, viewport_control: Gd<Control>) {
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.draw_over_viewport(viewport_control);
+        }
+    }
+
+    fn forward_3d_gui_input(&mut self, _camera: Gd<Camera3D>, event: Gd<InputEvent>) -> i32 {
+        let consumed = self
+            .overlay
+            .as_mut()
+            .is_some_and(|x| x.forward_gui_input(event));
+
+        if consumed {
+            AfterGuiInput::STOP.ord()
+        } else {
+            AfterGuiInput::PASS.ord()
+        }
+    }
+
     fn has_main_screen(&self) -> bool {
         true
     }
@@ -1,14 +1,18 @@
 use std::{
     cell::{Cell, RefCell},
-    collections::{hash_map, HashSet, VecDeque},
+    collections::{hash_map, HashMap, HashSet, VecDeque},
+    future::Future,
     mem::take,
+    pin::Pin,
+    rc::Rc,
     sync::{
         atomic::{
-            AtomicBool, AtomicU8,
+            AtomicBool, AtomicU64, AtomicU8,
             Ordering::{self, Relaxed},
         },
         mpsc, Arc,
     },
+    task::{Poll, Waker},
     thread::ThreadId,
     time::{Duration, Instant},
 };
@@ -20,18 +24,23 @@ use egui::{
 };
 use godot::{
     classes::{
-        self,
-        control::{LayoutPreset, MouseFilter},
-        window, CanvasLayer, Control, DisplayServer, ICanvasLayer, WeakRef,
-    }, global, prelude::*
+        self, control::LayoutPreset, node::ProcessMode, window, CanvasLayer, Control,
+        DisplayServer, Engine, ICanvasLayer, WeakRef,
+    },
+    global,
+    prelude::*,
 };
 use tap::prelude::{Pipe, Tap};
 use with_drop::with_drop;
 
 use crate::{
     default,
-    helpers::{downgrade_gd, try_upgrade_gd, ToCounterpart},
-    surface,
+    helpers::{downgrade_gd, resolve_user_path, try_upgrade_gd, ToCounterpart},
+    surface::{self, InputMode},
+    widgets::{
+        apply_input_overrides, Console, ConsoleArgs, ConsoleCommands, FnPanelDraw, InputCapture,
+        InputRebinder, MenuItemOptions, PanelGroup, SpawnedWidgetContext,
+    },
 };
 
 /* ---------------------------------------------------------------------------------------------- */
@@ -55,8 +64,92 @@ pub struct EguiBridge {
     #[init(val = 13)]
     pub max_texture_bits: u8,
 
+    /// When the window of this bridge embeds subwindows, egui viewports are spawned as
+    /// embedded Godot windows, instead of being drawn inside the root surface.
+    ///
+    /// Godot draws the decorations of embedded windows, so they're still movable,
+    /// resizable and closable on single-window platforms.
+    #[export]
+    #[var(get, set)]
+    pub embedded_subwindows: bool,
+
+    /// Zoom multiplier applied on top of the screen scale of each viewport.
+    #[export]
+    #[var(get, set)]
+    #[init(val = 1.)]
+    pub ui_zoom: f32,
+
+    /// File to persist egui memory and window geometry of viewports across sessions.
+    /// Relative paths are resolved under `user://`. Empty string disables persistence.
+    #[export]
+    #[var(get, set)]
+    pub persistence_path: GString,
+
+    /// File to persist the layout of dock panels; see [`Self::dock_panel_register`].
+    /// Relative paths are resolved under `user://`; e.g. `egui_dock_layout.ron`. Empty
+    /// string disables persistence.
+    ///
+    /// Nothing is saved unless any panel is registered. Give each bridge its own file.
+    #[export]
+    #[var(get, set)]
+    pub dock_layout_path: GString,
+
+    /// File to persist input bindings changed by [`Self::input_rebinder`]; e.g.
+    /// `input_overrides.cfg`. Relative paths are resolved under `user://`. Empty string
+    /// disables persistence.
+    ///
+    /// Saved bindings are applied to `InputMap` once, when the bridge enters the tree for
+    /// the first time; never in the editor. See also [`crate::widgets::apply_input_overrides`].
+    #[export]
+    #[var(get, set)]
+    pub input_overrides_path: GString,
+
+    /// Skip egui frames, including tessellation and drawing, while there's no pending
+    /// input or repaint request. Frames started by [`Self::current_frame`] are not
+    /// skipped; use [`Self::try_current_frame`] instead.
+    ///
+    /// Suitable for tool scenes and editor plugins, along with
+    /// `OS.low_processor_usage_mode`.
+    #[export]
+    #[var(get, set)]
+    pub reactive: bool,
+
+    /// Number of process ticks which didn't run any egui frame, while reactive.
+    skipped_frames: u64,
+
+    /// Input action which toggles visibility of the main menu bar. Empty string disables
+    /// the toggle.
+    #[export]
+    #[var(get, set)]
+    pub menu_bar_toggle_action: StringName,
+
+    /// How this bridge behaves while the scene tree is paused. Every policy except
+    /// [`PausePolicy::Inherit`] overrides the `process_mode` of the bridge.
+    #[export]
+    #[var(get, set = set_pause_policy)]
+    #[init(val = PausePolicy::Inherit)]
+    pub pause_policy: PausePolicy,
+
+    /// `process_mode` of the bridge before [`Self::pause_policy`] overrode it.
+    overridden_process_mode: Option<ProcessMode>,
+
+    /// Input is being ignored by [`PausePolicy::FreezeInput`].
+    input_frozen: Cell<bool>,
+
+    /// Process frame number when the current egui frame started.
+    frame_started_at: Cell<u64>,
+
+    /// Statistics of the current frame, being measured.
+    frame_stats: Cell<FrameStats>,
+
+    /// Start time of viewport frames being built; immediate viewports are nested.
+    viewport_build_started: RefCell<Vec<Instant>>,
+
+    /// Window states loaded from persistence file, applied when each viewport spawns.
+    persisted_viewports: RefCell<ViewportIdMap<PersistedViewport>>,
+
     /// Texture storage
-    textures: surface::TextureLibrary,
+    textures: RefCell<surface::TextureLibrary>,
 
     /// Pending intra-frame access methods
 
@@ -73,6 +166,16 @@ pub struct EguiBridge {
     /// Determines the cursor shape of this frame.
     cursor_shape: RefCell<Option<egui::CursorIcon>>,
 
+    /// Cursor grab / visibility overridden by viewport commands.
+    cursor_override: RefCell<Option<CursorOverride>>,
+
+    /// Screenshot requests waiting for the viewport to be drawn.
+    screenshot_tasks: RefCell<Vec<ScreenshotTask>>,
+
+    /// Viewports that are hosted by existing nodes, instead of windows spawned by this
+    /// bridge.
+    guest_hosts: RefCell<ViewportIdMap<GuestHost>>,
+
     /// Handle to tesselation worker tx channel
     tx_bg_task: RefCell<Option<mpsc::Sender<DeferredCommand>>>,
     rx_bg_task: RefCell<Option<mpsc::Receiver<DeferredCommand>>>,
@@ -81,8 +184,24 @@ pub struct EguiBridge {
     root_region_sync: Cell<Option<Gd<WeakRef>>>,
 
     /// callbacks for widgets rendering.
-    widget_callbacks_first: RefCell<Vec<(i32, Box<FnWidgetCallback>)>>,
-    widget_callbacks_last: RefCell<Vec<(i32, Box<FnWidgetCallback>)>>,
+    widget_callbacks_first: RefCell<Vec<RegisteredCallback>>,
+    widget_callbacks_last: RefCell<Vec<RegisteredCallback>>,
+
+    /// Callbacks registered with a key, which are replaced on re-registration.
+    widget_keys: RefCell<HashMap<String, Rc<WidgetSlot>>>,
+
+    /// Items of predefined panels; see [`PanelGroup`].
+    widgets: SpawnedWidgetContext,
+
+    /// Commands shared by consoles of this bridge; see [`Self::console`].
+    console_commands: ConsoleCommands,
+
+    /// Slot which captures input for rebinders, shared with painters.
+    input_capture: InputCapture,
+
+    /// Input overrides were applied already. Re-entering the tree must not revert the
+    /// bindings changed since then.
+    input_overrides_applied: Cell<bool>,
 
     /// non-send + non-sync even when threading is implemented for godot objects ...
     _non_send_sync: std::marker::PhantomData<*const ()>,
@@ -92,8 +211,132 @@ type FnWidgetCallback = dyn FnMut(&egui::Context) -> WidgetRetain + 'static;
 
 enum DeferredCommand {
     RequestRepaint(ViewportId),
+
+    /// Work posted from [`EguiHandle`], which runs on the main thread.
+    Run(Box<dyn FnOnce(&EguiBridge) + Send + 'static>),
+}
+
+/// Behavior of [`EguiBridge`] while the scene tree is paused.
+#[derive(GodotConvert, Var, Export, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[godot(via = i64)]
+pub enum PausePolicy {
+    /// Leaves `process_mode` of the bridge as set by user; by default, it's inherited
+    /// from the parent node. Switching back from other policies restores the mode they
+    /// overrode.
+    #[default]
+    Inherit = 3,
+
+    /// Keeps updating and receiving input; e.g. pause menus built with egui.
+    AlwaysRun = 0,
+
+    /// Stops updating along with the tree. Frames started explicitly during pause (e.g.
+    /// from nodes with `PROCESS_MODE_ALWAYS`) are still finished on the next frame start.
+    PauseWithTree = 1,
+
+    /// Keeps drawing, however, ignores any input while the tree is paused.
+    FreezeInput = 2,
+}
+
+/// Statistics of an egui frame, measured by [`EguiBridge`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FrameStats {
+    /// Time spent in UI code, from start to end of each viewport frame.
+    pub build_time: Duration,
+
+    /// Time spent on tessellating shapes of every viewport.
+    pub tessellation_time: Duration,
+
+    /// Number of canvas items of every painter, after drawing.
+    pub canvas_items: usize,
+
+    /// Number of viewports drawn in this frame.
+    pub viewports_drawn: usize,
+}
+
+impl FrameStats {
+    fn id() -> egui::Id {
+        egui::Id::new("%%EguiBridge%%FrameStats")
+    }
+
+    /// Statistics of the last finished frame of the bridge which owns given context.
+    /// Available from render callbacks, where the bridge itself is not accessible.
+    pub fn load(ctx: &egui::Context) -> Option<Self> {
+        ctx.data(|x| x.get_temp(Self::id()))
+    }
+}
+
+/// Input actions which wake the bridge up for a frame when just pressed, even if nothing
+/// requested a repaint. Render callbacks, which only run within frames, register here the
+/// actions they poll; e.g. toggle of a hidden overlay.
+pub(crate) struct WakeActions;
+
+impl WakeActions {
+    fn id() -> egui::Id {
+        egui::Id::new("%%EguiBridge%%WakeActions")
+    }
+
+    /// Register an action to the bridge which owns given context.
+    pub fn insert(ctx: &egui::Context, action: &StringName) {
+        let action = action.to_string();
+
+        ctx.data_mut(|x| {
+            let actions = x.get_temp_mut_or_default::<Vec<String>>(Self::id());
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        });
+    }
+
+    fn load(ctx: &egui::Context) -> Vec<String> {
+        ctx.data(|x| x.get_temp(Self::id())).unwrap_or_default()
+    }
+}
+
+/// Cursor state requested through viewport commands. Since these are global state of
+/// the engine, we should revert them once the requesting viewport is gone.
+struct CursorOverride {
+    /// Viewport which requested the last cursor state change.
+    owner: ViewportId,
+
+    /// Mouse mode before any override was applied.
+    restore: classes::input::MouseMode,
+
+    grab: egui::CursorGrab,
+    visible: bool,
+}
+
+/// State of this bridge which is saved across sessions.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PersistedState {
+    memory: egui::Memory,
+    viewports: Vec<(ViewportId, PersistedViewport)>,
+}
+
+/// Window state of a viewport, which is saved across sessions.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct PersistedViewport {
+    /// Position is restored from the outer rect, while the size is restored from the
+    /// inner size; window decorations are not part of `InnerSize`.
+    outer_rect: egui::Rect,
+    inner_size: egui::Vec2,
+    maximized: bool,
+    visible: bool,
 }
 
+/// A screenshot request, which is captured after the viewport is drawn by the engine.
+struct ScreenshotTask {
+    viewport: ViewportId,
+
+    /// Set when the frame that the request was made is finished. Since the rendering
+    /// server draws after every `process` call, the capture is taken on the next frame.
+    armed: bool,
+
+    /// Receives the captured image.
+    deliver: Box<FnScreenshot>,
+}
+
+type FnScreenshot = dyn FnOnce(&EguiBridge, Gd<classes::Image>) + 'static;
+
 #[derive(Clone)]
 struct SurfaceContext {
     /// Actual painter window.
@@ -101,6 +344,40 @@ struct SurfaceContext {
 
     /// Container window if exist.
     window: Option<Gd<classes::Window>>,
+
+    /// Host node, if this surface is attached to a node that isn't owned by this bridge.
+    guest: Option<GuestHost>,
+
+    /// Close request subscription to the guest host window; disconnected on dispose.
+    on_guest_close: Option<Callable>,
+}
+
+/// A node which hosts a guest viewport. Owned by the user; we never free it.
+#[derive(Clone, PartialEq)]
+enum GuestHost {
+    Window(Gd<classes::Window>),
+    SubViewport(Gd<classes::SubViewport>),
+
+    /// Input is delivered by user through [`EguiBridge::viewport_forward_input`].
+    Overlay(Gd<Control>),
+}
+
+impl GuestHost {
+    fn node(&self) -> Gd<Node> {
+        match self {
+            GuestHost::Window(x) => x.clone().upcast(),
+            GuestHost::SubViewport(x) => x.clone().upcast(),
+            GuestHost::Overlay(x) => x.clone().upcast(),
+        }
+    }
+
+    fn is_instance_valid(&self) -> bool {
+        match self {
+            GuestHost::Window(x) => x.is_instance_valid(),
+            GuestHost::SubViewport(x) => x.is_instance_valid(),
+            GuestHost::Overlay(x) => x.is_instance_valid(),
+        }
+    }
 }
 
 #[derive(Educe)]
@@ -111,6 +388,10 @@ struct SharedContext {
     /// Repaint was queued.
     repaint_queued: AtomicBool,
 
+    /// Mirror of [`EguiBridge::reactive`], synced every tick. Otherwise, every repaint
+    /// request is queued right away, regardless of its delay.
+    reactive: AtomicBool,
+
     /// Detects whether to start new frame.
     frame_started: AtomicBool,
 
@@ -155,10 +436,19 @@ struct ViewportContext {
     /// Close request status
     close_request: Arc<ViewportClose>,
 
+    /// Whether the close button of the window is enabled. If disabled, close requests
+    /// from the platform are silently ignored.
+    close_enabled: Arc<AtomicBool>,
+
     /// Viewport commands pending apply. When should be recreated, the second parameter
     /// set to [`Some`].
     updates: Vec<egui::ViewportCommand>,
 
+    /// Events generated by the backend itself (e.g. screenshot replies), or received
+    /// input held while skipping frames, which will be delivered on the next frame of this
+    /// viewport.
+    pending_events: Vec<egui::Event>,
+
     /// Paint commands that is being applied,
     paint_this_frame: Option<Vec<egui::ClippedPrimitive>>,
 
@@ -168,6 +458,13 @@ struct ViewportContext {
 
     /// Cached viewport information, that we're currently updating on.
     info: egui::ViewportInfo,
+
+    /// Screen that the viewport was on, when the information was last updated.
+    screen: Option<i32>,
+
+    /// Font atlas updates generated by this viewport's frame, with the pixels-per-point
+    /// that they're rasterized for.
+    font_deltas: Vec<(f32, egui::epaint::ImageDelta)>,
 }
 
 /// Closing steps
@@ -191,6 +488,51 @@ const VIEWPORT_CLOSE_CLOSE: u8 = 3;
 /// Callback for deferred context access, for non-rendering purposes.
 type FnDeferredContextAccess = dyn FnOnce(&egui::Context) + 'static;
 
+/* ------------------------------------------ Registry ------------------------------------------ */
+
+thread_local! {
+    /// Bridges which are initiated, with their egui context. Used to find the owner of
+    /// a context, and to tear them down before the library unloads.
+    static LIVE_BRIDGES: RefCell<Vec<(InstanceId, egui::Context)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Immediate viewport renderer, which is shared by every bridge. Since egui holds only a
+/// single renderer globally, this finds the bridge which owns the context, then renders
+/// the viewport on its surfaces.
+fn render_immediate_viewport(ctx: &egui::Context, viewport: egui::ImmediateViewport<'_>) {
+    let owner =
+        LIVE_BRIDGES.with_borrow(|x| x.iter().find(|(_, egui)| egui == ctx).map(|(id, _)| *id));
+
+    let Some(Ok(this)) = owner.map(Gd::<EguiBridge>::try_from_instance_id) else {
+        godot_warn!("Immediate viewport requested from unknown egui context");
+        return;
+    };
+
+    let this = this.bind();
+
+    this.viewport_validate(
+        viewport.ids.this,
+        Some((viewport.ids.parent, viewport.builder)),
+    );
+    this.viewport_start_frame(viewport.ids.this);
+
+    (viewport.viewport_ui_cb)(ctx);
+    this.viewport_end_frame(viewport.ids.this);
+}
+
+/// Tear down every live bridge, and clear the global immediate renderer.
+pub(crate) fn teardown_all() {
+    for (id, _) in LIVE_BRIDGES.with_borrow_mut(take) {
+        if let Ok(mut bridge) = Gd::<EguiBridge>::try_from_instance_id(id) {
+            bridge.bind_mut().teardown();
+        }
+    }
+
+    // Nothing should point the code of this library.
+    egui::Context::set_immediate_viewport_renderer(|_, _| {});
+}
+
 /* --------------------------------- Widget Lifetime Control -------------------------------- */
 
 /// Every spawned widgets are retained as long as the callback returns true.
@@ -235,25 +577,126 @@ impl From<()> for WidgetRetain {
     }
 }
 
+struct RegisteredCallback {
+    slot: Rc<WidgetSlot>,
+    show: Box<FnWidgetCallback>,
+}
+
+/// State of registered callback, shared with its [`WidgetHandle`].
+struct WidgetSlot {
+    priority: Cell<i32>,
+    visible: Cell<bool>,
+    disposed: Cell<bool>,
+    share: std::sync::Weak<SharedContext>,
+}
+
+impl WidgetSlot {
+    fn request_repaint(&self) {
+        if let Some(share) = self.share.upgrade() {
+            share.repaint_queued.store(true, Relaxed);
+        }
+    }
+}
+
+/// Handle to a registered render callback.
+///
+/// The callback is disposed as soon as the handle is dropped, unless it's detached with
+/// [`WidgetHandle::detach`].
+#[must_use = "callback is disposed as soon as the handle is dropped; use `detach()` to keep it"]
+pub struct WidgetHandle {
+    slot: Rc<WidgetSlot>,
+    detached: bool,
+}
+
+impl WidgetHandle {
+    /// Dispose the callback. It won't be called anymore.
+    pub fn dispose(&self) {
+        if !self.slot.disposed.replace(true) {
+            self.slot.request_repaint();
+        }
+    }
+
+    /// Whether the callback is disposed; either by this handle, or by returning
+    /// [`WidgetRetain::Dispose`].
+    pub fn is_disposed(&self) -> bool {
+        self.slot.disposed.get()
+    }
+
+    /// Hidden callbacks are retained, but not called.
+    pub fn set_visible(&self, visible: bool) {
+        if self.slot.visible.replace(visible) != visible {
+            self.slot.request_repaint();
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.slot.visible.get()
+    }
+
+    /// Callbacks with lower priority will be called earlier.
+    pub fn set_priority(&self, priority: i32) {
+        if self.slot.priority.replace(priority) != priority {
+            self.slot.request_repaint();
+        }
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.slot.priority.get()
+    }
+
+    /// Release the handle without disposing the callback. Then the callback is retained
+    /// until it returns [`WidgetRetain::Dispose`].
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl Drop for WidgetHandle {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.dispose();
+        }
+    }
+}
+
 /* ------------------------------------------ Godot Api ----------------------------------------- */
 
 #[godot_api]
 impl ICanvasLayer for EguiBridge {
     fn process(&mut self, _dt: f64) {
+        self.share.reactive.store(self.reactive, Relaxed);
         self.handle_bg_message();
 
-        if self.share.repaint_queued.swap(false, Relaxed) {
+        // Windows may have been moved to another screen, without any input.
+        self.poll_screen_changes();
+
+        // Viewports drawn on the last frame are now rendered; capture them.
+        self.capture_screenshots();
+
+        self.update_input_freeze();
+        self.poll_menu_bar_toggle();
+        self.poll_wake_actions();
+
+        // In reactive mode, delayed repaints are checked every tick; Godot keeps processing
+        // even in low processor usage mode, and the drawing from the new frame wakes the
+        // renderer.
+        if self.share.repaint_queued.swap(false, Relaxed)
+            || (self.reactive && self.has_pending_update())
+        {
             self.current_frame();
         }
 
         if self.share.is_in_frame() {
             self.finish_frame();
+        } else if self.reactive {
+            self.skipped_frames += 1;
         }
 
         self.handle_bg_message();
     }
 
     fn enter_tree(&mut self) {
+        self.apply_pause_policy();
         self.try_initiate();
     }
 
@@ -268,6 +711,119 @@ impl EguiBridge {
     fn __internal_try_start_frame_inner(&self) {
         self.try_start_frame();
     }
+
+    #[func]
+    pub fn set_pause_policy(&mut self, policy: PausePolicy) {
+        self.pause_policy = policy;
+        self.apply_pause_policy();
+        self.update_input_freeze();
+    }
+
+    /// Discard the user arranged layout of dock panels; every registered panel is put in
+    /// a single tab group, in registration order.
+    #[func]
+    pub fn dock_reset_layout(&self) {
+        self.widgets.dock.reset_layout();
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Register a console command, which calls given callable with parsed arguments;
+    /// integers, floats and booleans are parsed, and the rest are passed as strings. The
+    /// returned value is printed to the console, unless it's null.
+    ///
+    /// See [`Self::console`] and [`Self::console_command_register`].
+    #[func]
+    pub fn console_command_register_callable(
+        &self,
+        name: GString,
+        description: GString,
+        callable: Callable,
+    ) {
+        self.console_commands.register_callable(
+            name.to_string(),
+            description.to_string(),
+            callable,
+        );
+    }
+
+    /// Remove the console command of given name, which was registered either from Rust or
+    /// GDScript.
+    #[func]
+    pub fn console_command_unregister(&self, name: GString) {
+        self.console_commands.unregister(&name.to_string());
+    }
+
+    /// Statistics of the last finished frame. See also [`FrameStats::load`].
+    pub fn frame_stats(&self) -> FrameStats {
+        FrameStats::load(&self.share.egui).unwrap_or_default()
+    }
+
+    /// Number of process ticks which didn't run any egui frame in [`Self::reactive`] mode.
+    /// Ticks are not counted while the bridge is not reactive.
+    #[func]
+    pub fn get_skipped_frames(&self) -> i64 {
+        self.skipped_frames as i64
+    }
+
+    /// Save egui memory and window geometry into [`Self::persistence_path`]. This is
+    /// done automatically when the bridge exits the tree.
+    #[func]
+    pub fn save_persistence(&self) {
+        let Some(path) = self.resolved_persistence_path() else {
+            return;
+        };
+
+        let memory = self.share.egui.memory(|x| x.clone());
+        let viewports = {
+            // Lock order: `share.viewports` -> `surfaces`
+            let viewports = self.share.viewports.lock();
+            let surfaces = self.surfaces.borrow();
+
+            surfaces
+                .iter()
+                .filter_map(|(id, surface)| {
+                    // Only the windows that we own; hosts of guests are user's.
+                    let window = surface.window.as_ref()?;
+                    let info = &viewports.get(id)?.info;
+
+                    Some((
+                        *id,
+                        PersistedViewport {
+                            outer_rect: info.outer_rect?,
+                            inner_size: info.inner_rect?.size(),
+                            maximized: info.maximized.unwrap_or(false),
+                            visible: window.is_visible(),
+                        },
+                    ))
+                })
+                // Keep the states of viewports that weren't spawned in this session.
+                .chain(
+                    self.persisted_viewports
+                        .borrow()
+                        .iter()
+                        .map(|(id, x)| (*id, x.clone())),
+                )
+                .collect()
+        };
+
+        let payload = match ron::to_string(&PersistedState { memory, viewports }) {
+            Ok(x) => x,
+            Err(e) => {
+                godot_error!("Failed to serialize egui state: {e}");
+                return;
+            }
+        };
+
+        let Some(mut file) =
+            classes::FileAccess::open(&path, classes::file_access::ModeFlags::WRITE)
+        else {
+            let err = classes::FileAccess::get_open_error();
+            godot_error!("Failed to open '{path}' to save egui state: {err:?}");
+            return;
+        };
+
+        file.store_string(&payload);
+    }
 }
 
 /* -------------------------------------------- APIs -------------------------------------------- */
@@ -287,6 +843,16 @@ impl EguiBridge {
         }
     }
 
+    /// Create a handle which can be sent to other threads, to post UI works onto the main
+    /// thread. See [`EguiHandle`].
+    pub fn handle(&self) -> EguiHandle {
+        self.try_initiate();
+
+        EguiHandle {
+            tx: self.tx_bg_task.borrow().clone().unwrap(),
+        }
+    }
+
     /// Synchronize root viewport's region with given control. If [`None`] is given, it
     /// unregisters synchronization.
     pub fn sync_root_region(&self, target: Option<Gd<Control>>) {
@@ -319,6 +885,26 @@ impl EguiBridge {
         &self.share.egui
     }
 
+    /// Same as [`Self::current_frame`], however, in [`Self::reactive`] mode, this returns
+    /// [`None`] if there's nothing to update; then you can skip the UI code.
+    ///
+    /// ```no_run
+    /// # use godot::prelude::*;
+    /// # use gdext_egui::*;
+    /// # let bridge = EguiBridge::new_alloc();
+    /// if let Some(ctx) = bridge.bind().try_current_frame() {
+    ///     egui::Window::new("Tool").show(ctx, |ui| ui.label("Hello"));
+    /// }
+    /// ```
+    pub fn try_current_frame(&self) -> Option<&egui::Context> {
+        let skip = self.reactive
+            && !self.share.is_in_frame()
+            && !self.share.repaint_queued.load(Relaxed)
+            && !self.has_pending_update();
+
+        (!skip).then(|| self.current_frame())
+    }
+
     /// Render viewport for current frame.
     ///
     /// This is shortcut to following code.
@@ -416,12 +1002,17 @@ impl EguiBridge {
     /// See also [`FnEguiDrawExt`] decorator for every method with signature
     /// `impl FnMut(&egui::Context) -> impl Into<WidgetRetain> + 'static`
     ///
-    /// Callbacks registered with lower priority will be called earlier.
-    pub fn register_render_callback_first<L>(&self, priority: i32, widget: impl FnEguiDraw<L>)
+    /// Callbacks registered with lower priority will be called earlier. The callback is
+    /// disposed when the returned handle is dropped; see [`WidgetHandle::detach`].
+    pub fn register_render_callback_first<L>(
+        &self,
+        priority: i32,
+        widget: impl FnEguiDraw<L>,
+    ) -> WidgetHandle
     where
         L: Into<WidgetRetain>,
     {
-        self.impl_push_panel_item(true, priority, widget);
+        self.impl_push_panel_item(true, None, priority, widget)
     }
 
     /// Registers callback for widget rendering at frame end.
@@ -429,19 +1020,84 @@ impl EguiBridge {
     /// See also [`FnEguiDrawExt`] decorator for every method with signature
     /// `impl FnMut(&egui::Context) -> impl Into<WidgetRetain> + 'static`
     ///
-    /// Callbacks registered with lower priority will be called earlier.
-    pub fn register_render_callback_last<L>(&self, priority: i32, widget: impl FnEguiDraw<L>)
+    /// Callbacks registered with lower priority will be called earlier. The callback is
+    /// disposed when the returned handle is dropped; see [`WidgetHandle::detach`].
+    pub fn register_render_callback_last<L>(
+        &self,
+        priority: i32,
+        widget: impl FnEguiDraw<L>,
+    ) -> WidgetHandle
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.impl_push_panel_item(false, None, priority, widget)
+    }
+
+    /// Same as [`Self::register_render_callback_first`], however, any callback previously
+    /// registered with the same key is disposed; e.g. re-registering a debug panel on
+    /// scene reload replaces it instead of duplicating it.
+    pub fn register_keyed_render_callback_first<L>(
+        &self,
+        key: impl Into<String>,
+        priority: i32,
+        widget: impl FnEguiDraw<L>,
+    ) -> WidgetHandle
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.impl_push_panel_item(true, Some(key.into()), priority, widget)
+    }
+
+    /// Keyed version of [`Self::register_render_callback_last`]. See
+    /// [`Self::register_keyed_render_callback_first`].
+    pub fn register_keyed_render_callback_last<L>(
+        &self,
+        key: impl Into<String>,
+        priority: i32,
+        widget: impl FnEguiDraw<L>,
+    ) -> WidgetHandle
     where
         L: Into<WidgetRetain>,
     {
-        self.impl_push_panel_item(false, priority, widget);
+        self.impl_push_panel_item(false, Some(key.into()), priority, widget)
+    }
+
+    /// Find a live callback registered with given key.
+    ///
+    /// Returned handle is detached; dropping it doesn't dispose the callback.
+    pub fn find_render_callback(&self, key: &str) -> Option<WidgetHandle> {
+        let slot = self.widget_keys.borrow().get(key).cloned()?;
+
+        (!slot.disposed.get()).then_some(WidgetHandle {
+            slot,
+            detached: true,
+        })
     }
 
     /// Registers callback for widget rendering, at very first of the frame start.
-    fn impl_push_panel_item<L>(&self, first: bool, priority: i32, mut widget: impl FnEguiDraw<L>)
+    fn impl_push_panel_item<L>(
+        &self,
+        first: bool,
+        key: Option<String>,
+        priority: i32,
+        mut widget: impl FnEguiDraw<L>,
+    ) -> WidgetHandle
     where
         L: Into<WidgetRetain>,
     {
+        let slot = Rc::new(WidgetSlot {
+            priority: Cell::new(priority),
+            visible: Cell::new(true),
+            disposed: Cell::new(false),
+            share: Arc::downgrade(&self.share),
+        });
+
+        if let Some(key) = key {
+            if let Some(prev) = self.widget_keys.borrow_mut().insert(key, slot.clone()) {
+                prev.disposed.set(true);
+            }
+        }
+
         let show = Box::new(move |ui: &_| widget(ui).into());
         let mut arr = if first {
             self.widget_callbacks_first.borrow_mut()
@@ -449,40 +1105,726 @@ impl EguiBridge {
             self.widget_callbacks_last.borrow_mut()
         };
 
-        let insert_index = arr
-            .binary_search_by_key(&priority, |(p, ..)| *p)
-            .unwrap_or_else(|x| x);
-
-        arr.insert(insert_index, (priority, show));
+        // Order is settled right before the callbacks are invoked.
+        arr.push(RegisteredCallback {
+            slot: slot.clone(),
+            show,
+        });
         self.share.repaint_queued.store(true, Relaxed);
+
+        WidgetHandle {
+            slot,
+            detached: false,
+        }
     }
 
-    /// Spawn new viewport as child of existing node. If specified parent node is behind
-    /// other node, the input may work naturally as the egui surface always intercepts any
-    /// GUI input. It is advised to use this method for any node that lays over any other
-    /// GUI nodes, which makes all egui rendering appear always top of the other GUI
-    /// nodes.
-    pub fn viewport_spawn_as_child(
+    /// Capture the image of given viewport, after it is drawn. The callback is invoked on
+    /// main thread, a frame later than the request.
+    ///
+    /// This is what [`egui::ViewportCommand::Screenshot`] uses internally; the command
+    /// delivers the result as [`egui::Event::Screenshot`] instead.
+    pub fn viewport_screenshot(
         &self,
-        _id: ViewportId,
-        _parent: Gd<Control>,
-        _builder: ViewportBuilder,
-        _show: impl FnOnce(&egui::Context) -> WidgetRetain + 'static,
+        id: ViewportId,
+        on_capture: impl FnOnce(Gd<classes::Image>) + 'static,
     ) {
-    }
+        self.screenshot_tasks.borrow_mut().push(ScreenshotTask {
+            viewport: id,
+            armed: false,
+            deliver: Box::new(move |_, image| on_capture(image)),
+        });
 
-    /// Attach given node to given viewport's window.
-    ///
-    /// TODO: Implement this!
-    pub fn attach_node_to_viewport(&self, _id: ViewportId, node: Gd<Node>) -> Result<(), Gd<Node>> {
-        Err(node)
+        // Ensure there's a frame to finish, which arms the request.
+        self.share.repaint_queued.store(true, Relaxed);
     }
-}
 
-/* ------------------------------------------ Privates ------------------------------------------ */
+    /// Capture given viewport and save it as PNG file. Relative paths are resolved
+    /// under `user://`.
+    pub fn viewport_screenshot_save_png(&self, id: ViewportId, path: impl Into<String>) {
+        let path = resolve_user_path(path.into());
 
-/// Private API implementations
-impl EguiBridge {
+        self.viewport_screenshot(id, move |image| {
+            let err = image.save_png(&path);
+
+            if err != global::Error::OK {
+                godot_error!("Failed to save screenshot to '{path}': {err:?}");
+            }
+        });
+    }
+
+    /// Host given viewport in an existing window, instead of spawning a new one. The
+    /// painter fills the whole window, and the window's size and close request are
+    /// delivered to the viewport. Nothing is applied back to the window from viewport
+    /// builder or commands, as it's owned by the caller.
+    ///
+    /// The viewport itself should still be shown through [`Self::viewport_spawn`] or
+    /// [`egui::Context::show_viewport_deferred`] with the same id. Attaching a viewport
+    /// which is already shown moves it into the host on next frame.
+    pub fn viewport_attach_to_window(&self, id: ViewportId, host: Gd<classes::Window>) {
+        self.viewport_attach(id, GuestHost::Window(host));
+    }
+
+    /// Host given viewport inside an existing [`classes::SubViewport`]. Unlike the
+    /// window host, the viewport cannot be closed by the platform; call
+    /// [`Self::viewport_detach`] to close it.
+    ///
+    /// See [`Self::viewport_attach_to_window`] for details.
+    pub fn viewport_attach_to_subviewport(&self, id: ViewportId, host: Gd<classes::SubViewport>) {
+        self.viewport_attach(id, GuestHost::SubViewport(host));
+    }
+
+    /// Host given viewport as an overlay of existing control, which fills the control. The
+    /// painter doesn't receive any input by itself; deliver it through
+    /// [`Self::viewport_forward_input`]. (e.g. from `EditorPlugin::forward_canvas_gui_input`)
+    ///
+    /// See [`Self::viewport_attach_to_window`] for details.
+    pub fn viewport_attach_to_overlay(&self, id: ViewportId, host: Gd<Control>) {
+        self.viewport_attach(id, GuestHost::Overlay(host));
+    }
+
+    /// Deliver an input event to given viewport. Returns true if egui consumed the input.
+    ///
+    /// Mouse positions are expected to be local to the painter's parent.
+    pub fn viewport_forward_input(&self, id: ViewportId, event: Gd<classes::InputEvent>) -> bool {
+        let Some(mut painter) = self
+            .surfaces
+            .borrow()
+            .get(&id)
+            .map(|x| x.painter.clone())
+            .filter(|x| x.is_instance_valid())
+        else {
+            return false;
+        };
+
+        let consumed = painter.bind_mut().try_consume_input(event);
+        consumed
+    }
+
+    /// Whether this bridge currently holds a surface for given viewport; i.e. the viewport
+    /// was rendered by this bridge, and not closed since.
+    pub fn has_viewport(&self, id: ViewportId) -> bool {
+        self.surfaces.borrow().contains_key(&id)
+    }
+
+    /// Detach given guest viewport from its host, and close the viewport. The host node
+    /// is left untouched.
+    pub fn viewport_detach(&self, id: ViewportId) {
+        if self.guest_hosts.borrow_mut().remove(&id).is_none() {
+            return;
+        }
+
+        // Stop rendering it; surface will be disposed at the end of the frame.
+        self.share.spawned_viewports.lock().remove(&id);
+
+        if let Some(vp) = self.share.viewports.lock().get(&id) {
+            vp.close_request.store(VIEWPORT_CLOSE_CLOSE, Relaxed);
+        }
+
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    fn viewport_attach(&self, id: ViewportId, host: GuestHost) {
+        if id == ViewportId::ROOT {
+            godot_warn!("Root viewport cannot be attached to other host");
+            return;
+        }
+
+        self.guest_hosts.borrow_mut().insert(id, host);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Spawn new viewport as child of existing node. If specified parent node is behind
+    /// other node, the input may work naturally as the egui surface always intercepts any
+    /// GUI input. It is advised to use this method for any node that lays over any other
+    /// GUI nodes, which makes all egui rendering appear always top of the other GUI
+    /// nodes.
+    pub fn viewport_spawn_as_child(
+        &self,
+        _id: ViewportId,
+        _parent: Gd<Control>,
+        _builder: ViewportBuilder,
+        _show: impl FnOnce(&egui::Context) -> WidgetRetain + 'static,
+    ) {
+    }
+
+    /// Attach given node to given viewport's window.
+    ///
+    /// TODO: Implement this!
+    pub fn attach_node_to_viewport(&self, _id: ViewportId, node: Gd<Node>) -> Result<(), Gd<Node>> {
+        Err(node)
+    }
+}
+
+/* ------------------------------------------- Panels ------------------------------------------- */
+
+/// APIs for predefined panels of the root viewport. See [`PanelGroup`].
+impl EguiBridge {
+    /// Add a widget item to specified panel group with given order. It'll silently
+    /// replace the existing item if the slot is already occupied.
+    ///
+    /// For detailed information of predefined panels, see [`PanelGroup`].
+    ///
+    /// # Panics
+    ///
+    /// Slot range exceeds i32::MAX >> 1 or i32::MIN >> 1.
+    pub fn panel_item_insert<L>(&self, panel: PanelGroup, slot: i32, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.widgets.panel_item_insert(panel, slot, widget);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Add a widget item after every existing item of the panel group.
+    pub fn panel_item_push_back<L>(&self, panel: PanelGroup, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.widgets.panel_item_push_back(panel, widget);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Add a widget item before every existing item of the panel group.
+    pub fn panel_item_push_front<L>(&self, panel: PanelGroup, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.widgets.panel_item_push_front(panel, widget);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Show or hide given panel group. This is what the visibility toggle does. Both of
+    /// bottom groups are shown or hidden together.
+    pub fn set_panel_visible(&self, panel: PanelGroup, visible: bool) {
+        self.widgets.hide_flag(panel).set(!visible);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    pub fn is_panel_visible(&self, panel: PanelGroup) -> bool {
+        !self.widgets.hide_flag(panel).get()
+    }
+
+    /// Show or hide every panel at once, including the visibility toggle.
+    pub fn set_panels_visible(&self, visible: bool) {
+        self.widgets.hide_all.set(!visible);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Add a widget to the main menu bar by path. e.g. `["Debug", "Physics"]` draws the
+    /// widget inside `Debug` menu, as an item named `Physics`. This will silently replace
+    /// the existing item if path is already exist.
+    ///
+    /// Widget of an item which has children is drawn on top of its submenu. The item is
+    /// removed once the widget returns [`WidgetRetain::Dispose`].
+    ///
+    /// ```no_run
+    /// # use godot::prelude::*;
+    /// # use gdext_egui::{*, widgets::MenuItemOptions};
+    /// # let bridge = EguiBridge::new_alloc();
+    /// let options = MenuItemOptions::default();
+    ///
+    /// bridge.bind().menu_item_insert(["View", "Zoom"], options, |ui: &mut egui::Ui| {
+    ///     ui.label("Zoom");
+    /// });
+    /// ```
+    pub fn menu_item_insert<T, L>(
+        &self,
+        path: impl IntoIterator<Item = T>,
+        options: MenuItemOptions,
+        widget: impl FnPanelDraw<L>,
+    ) where
+        T: Into<String>,
+        L: Into<WidgetRetain>,
+    {
+        let path = path.into_iter().map(Into::into).collect();
+        self.widgets.menu_item_insert(path, options, widget);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Add a button to the main menu bar by path, labeled with the last segment of the
+    /// path. The action is invoked when the button is clicked, or the shortcut of
+    /// [`MenuItemOptions`] is pressed. The item is removed once the action returns
+    /// [`WidgetRetain::Dispose`].
+    pub fn menu_action_insert<T, L>(
+        &self,
+        path: impl IntoIterator<Item = T>,
+        options: MenuItemOptions,
+        action: impl FnMut() -> L + 'static,
+    ) where
+        T: Into<String>,
+        L: Into<WidgetRetain>,
+    {
+        let path = path.into_iter().map(Into::into).collect();
+        self.widgets.menu_action_insert(path, options, action);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Set options of the menu item, without touching its content. Useful to order
+    /// submenus which are created implicitly by paths of their children.
+    ///
+    /// Options can be set before inserting any item under the path; they're kept until
+    /// the item is removed by [`Self::menu_item_remove`].
+    pub fn menu_set_options<T: Into<String>>(
+        &self,
+        path: impl IntoIterator<Item = T>,
+        options: MenuItemOptions,
+    ) {
+        let path = path.into_iter().map(Into::into).collect();
+        self.widgets.menu_set_options(path, options);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Remove the menu item of given path, along with its children.
+    pub fn menu_item_remove<T: Into<String>>(&self, path: impl IntoIterator<Item = T>) {
+        let path = path.into_iter().map(Into::into).collect();
+        self.widgets.menu_item_remove(path);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Show or hide the main menu bar. Shortcuts of menu items are still available while
+    /// the bar is hidden. See also [`Self::menu_bar_toggle_action`].
+    pub fn set_menu_bar_visible(&self, visible: bool) {
+        self.widgets.hide_menu.set(!visible);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    pub fn is_menu_bar_visible(&self) -> bool {
+        !self.widgets.hide_menu.get()
+    }
+}
+
+/* ------------------------------------------- Console ------------------------------------------ */
+
+/// APIs for in-game consoles. See [`Console`].
+impl EguiBridge {
+    /// Create a console which shares the commands registered to this bridge.
+    pub fn console(&self) -> Console {
+        Console::with_commands(self.console_commands.clone())
+    }
+
+    /// Register a console command; it'll silently replace the existing command of the
+    /// same name. The returned text is printed to the console, and the error is printed
+    /// as an error.
+    ///
+    /// See [`Self::console_command_register_callable`] for GDScript, and
+    /// [`Self::console_command_unregister`] to remove it.
+    pub fn console_command_register(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        handler: impl FnMut(&ConsoleArgs) -> Result<String, String> + 'static,
+    ) {
+        self.console_commands
+            .register(name.into(), description.into(), Box::new(handler));
+    }
+}
+
+/* --------------------------------------- Input Rebinding -------------------------------------- */
+
+/// APIs for keybinding settings. See [`InputRebinder`].
+impl EguiBridge {
+    /// Create a rebinder which saves overrides to [`Self::input_overrides_path`]. Input of
+    /// this bridge is suppressed while the rebinder captures a new binding.
+    pub fn input_rebinder(&self) -> InputRebinder {
+        InputRebinder::with_capture(
+            self.input_capture.clone(),
+            self.resolved_input_overrides_path(),
+        )
+    }
+}
+
+/* -------------------------------------------- Dock -------------------------------------------- */
+
+/// APIs for dockable debug panels, which are laid out as tabs and splits in a floating
+/// window. The layout is arranged by user, and persisted in [`EguiBridge::dock_layout_path`].
+impl EguiBridge {
+    /// Register a dock panel with given ID. It'll silently replace the existing panel of
+    /// the same ID, while keeping its place in the layout.
+    ///
+    /// Panels which are not found in the restored layout are added to the focused tab
+    /// group. The panel is unregistered once the widget returns [`WidgetRetain::Dispose`].
+    pub fn dock_panel_register<L>(
+        &self,
+        id: impl Into<String>,
+        title: impl Into<String>,
+        mut widget: impl FnPanelDraw<L>,
+    ) where
+        L: Into<WidgetRetain>,
+    {
+        let show = Box::new(move |ui: &mut _| widget(ui).into());
+
+        if self.widgets.dock.register(id.into(), title.into(), show) {
+            // Bridges without any dock panel shouldn't grow a menu bar.
+            self.widgets.menu_action_insert(
+                vec!["View".into(), "Reset Dock Layout".into()],
+                MenuItemOptions::default().order(i32::MAX),
+                {
+                    let reset = self.widgets.dock.reset_trigger();
+                    move || reset.set(true)
+                },
+            );
+        }
+
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Remove the dock panel of given ID, along with its tab.
+    pub fn dock_panel_unregister(&self, id: impl Into<String>) {
+        self.widgets.dock.unregister(id.into());
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    /// Show or hide the dock window. Panels keep their place in the layout while hidden.
+    pub fn set_dock_visible(&self, visible: bool) {
+        self.widgets.dock.hidden.set(!visible);
+        self.share.repaint_queued.store(true, Relaxed);
+    }
+
+    pub fn is_dock_visible(&self) -> bool {
+        !self.widgets.dock.hidden.get()
+    }
+}
+
+/* ------------------------------------------- Handle ------------------------------------------- */
+
+/// A `Send + Sync` handle to [`EguiBridge`], which is useful for pushing UI updates from
+/// worker threads. (e.g. toasts, progress bars, log lines, ...)
+///
+/// Every method queues given work into the bridge, which is then processed on the main
+/// thread at the start of the next gameplay frame. Since closures are actually invoked
+/// on the main thread, they still must be `Send` to cross the thread boundary.
+///
+/// All methods return `false` if the bridge is already disposed; in this case, posted
+/// work is simply dropped. A handle created before the bridge is re-initiated(e.g.
+/// removed from the tree and added again) is not revived.
+#[derive(Clone)]
+pub struct EguiHandle {
+    tx: mpsc::Sender<DeferredCommand>,
+}
+
+impl EguiHandle {
+    /// Run given closure on the main thread, with the bridge.
+    pub fn run(&self, work: impl FnOnce(&EguiBridge) + Send + 'static) -> bool {
+        self.tx.send(DeferredCommand::Run(Box::new(work))).is_ok()
+    }
+
+    /// Access to egui context on the main thread. See [`EguiBridge::setup_context`].
+    pub fn setup_context(&self, setter: impl FnOnce(&egui::Context) + Send + 'static) -> bool {
+        self.run(move |bridge| bridge.setup_context(setter))
+    }
+
+    /// Request repaint of the root viewport.
+    pub fn request_repaint(&self) -> bool {
+        self.request_repaint_of(ViewportId::ROOT)
+    }
+
+    /// Request repaint of given viewport.
+    pub fn request_repaint_of(&self, id: ViewportId) -> bool {
+        self.tx.send(DeferredCommand::RequestRepaint(id)).is_ok()
+    }
+
+    /// Spawn new viewport on the main thread. See [`EguiBridge::viewport_spawn`].
+    pub fn viewport_spawn<L>(
+        &self,
+        id: ViewportId,
+        builder: ViewportBuilder,
+        show: impl FnMut(&egui::Context) -> L + Send + 'static,
+    ) -> bool
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.run(move |bridge| bridge.viewport_spawn(id, builder, show))
+    }
+
+    /// See [`EguiBridge::register_render_callback_first`]. The callback is detached; it's
+    /// disposed only when it returns [`WidgetRetain::Dispose`].
+    pub fn register_render_callback_first<L>(
+        &self,
+        priority: i32,
+        widget: impl FnEguiDraw<L> + Send,
+    ) -> bool
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.run(move |bridge| {
+            bridge
+                .register_render_callback_first(priority, widget)
+                .detach()
+        })
+    }
+
+    /// See [`EguiBridge::register_render_callback_last`]. The callback is detached; it's
+    /// disposed only when it returns [`WidgetRetain::Dispose`].
+    pub fn register_render_callback_last<L>(
+        &self,
+        priority: i32,
+        widget: impl FnEguiDraw<L> + Send,
+    ) -> bool
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.run(move |bridge| {
+            bridge
+                .register_render_callback_last(priority, widget)
+                .detach()
+        })
+    }
+}
+
+/* ------------------------------------------- Dialogs ------------------------------------------ */
+
+impl EguiBridge {
+    /// Spawn a dialog viewport, which resolves once `draw` returns a final value.
+    ///
+    /// The future resolves to [`None`] if the dialog is closed without a value; e.g. the
+    /// user closed the window, or the bridge is disposed. Dropping the future closes the
+    /// dialog.
+    ///
+    /// The future is not `Send`, and should be driven by the main thread; e.g. with
+    /// `godot::task::spawn`, or any other single threaded executor.
+    pub fn dialog<R: 'static>(
+        &self,
+        builder: ViewportBuilder,
+        mut draw: impl FnMut(&egui::Context) -> Option<R> + 'static,
+    ) -> DialogFuture<R> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        let id = ViewportId::from_hash_of(("gdext_egui::dialog", NEXT_ID.fetch_add(1, Relaxed)));
+        let state = Rc::new(RefCell::new(DialogState {
+            result: None,
+            closed: false,
+            waker: None,
+        }));
+
+        // Wakes the future up even if the viewport is dropped without being resolved.
+        let guard = with_drop(state.clone(), |state| DialogState::close(&state));
+
+        self.viewport_spawn(id, builder, move |ctx| {
+            if guard.borrow().closed {
+                // Future was dropped.
+                return WidgetRetain::Dispose;
+            }
+
+            let result = draw(ctx);
+
+            if result.is_none() && !ctx.input(|x| x.viewport().close_requested()) {
+                return WidgetRetain::Retain;
+            }
+
+            guard.borrow_mut().result = result;
+            DialogState::close(&guard);
+
+            WidgetRetain::Dispose
+        });
+
+        DialogFuture {
+            id,
+            state,
+            handle: self.handle(),
+        }
+    }
+
+    /// Shows a simple yes / no dialog. Closing the dialog is treated as `false`.
+    ///
+    /// ```no_run
+    /// # use godot::prelude::*;
+    /// # use gdext_egui::*;
+    /// # async fn f(bridge: Gd<EguiBridge>) {
+    /// let confirmed = bridge.bind().confirm("Delete save?");
+    ///
+    /// if confirmed.await {
+    ///     // delete ...
+    /// }
+    /// # }
+    /// ```
+    pub fn confirm(&self, message: impl Into<String>) -> impl Future<Output = bool> {
+        let message = message.into();
+        let builder = ViewportBuilder::default()
+            .with_title("Confirm")
+            .with_inner_size([320., 100.])
+            .with_resizable(false);
+
+        let dialog = self.dialog(builder, move |ctx| {
+            egui::CentralPanel::default()
+                .show(ctx, |ui| {
+                    ui.label(&message);
+                    ui.add_space(8.);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Yes").clicked() {
+                            Some(true)
+                        } else if ui.button("No").clicked() {
+                            Some(false)
+                        } else {
+                            None
+                        }
+                    })
+                    .inner
+                })
+                .inner
+        });
+
+        async move { dialog.await.unwrap_or(false) }
+    }
+}
+
+struct DialogState<R> {
+    result: Option<R>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl<R> DialogState<R> {
+    fn close(this: &RefCell<Self>) {
+        let waker = {
+            let mut state = this.borrow_mut();
+            state.closed = true;
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Future returned from [`EguiBridge::dialog`].
+#[must_use = "dialog is closed as soon as the future is dropped"]
+pub struct DialogFuture<R> {
+    id: ViewportId,
+    state: Rc<RefCell<DialogState<R>>>,
+    handle: EguiHandle,
+}
+
+impl<R> Future for DialogFuture<R> {
+    type Output = Option<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+
+        if state.closed {
+            Poll::Ready(state.result.take())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<R> Drop for DialogFuture<R> {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+
+        if !state.closed {
+            state.closed = true;
+            state.waker = None;
+
+            // Let the dialog viewport see the cancellation.
+            self.handle.request_repaint_of(self.id);
+        }
+    }
+}
+
+/* ----------------------------------------- Persistence ---------------------------------------- */
+
+impl EguiBridge {
+    fn resolved_persistence_path(&self) -> Option<String> {
+        let path = self.persistence_path.to_string();
+        (!path.is_empty()).then(|| resolve_user_path(path))
+    }
+
+    fn resolved_dock_layout_path(&self) -> Option<String> {
+        let path = self.dock_layout_path.to_string();
+        (!path.is_empty()).then(|| resolve_user_path(path))
+    }
+
+    fn resolved_input_overrides_path(&self) -> Option<String> {
+        let path = self.input_overrides_path.to_string();
+        (!path.is_empty()).then(|| resolve_user_path(path))
+    }
+
+    /// Load persisted state, if exist. Memory is restored immediately, while the window
+    /// states are applied when each viewport spawns.
+    fn load_persistence(&self) {
+        let Some(path) = self.resolved_persistence_path() else {
+            return;
+        };
+
+        if !classes::FileAccess::file_exists(&path) {
+            // Nothing saved yet.
+            return;
+        }
+
+        let payload = classes::FileAccess::get_file_as_string(&path).to_string();
+        let state = match ron::from_str::<PersistedState>(&payload) {
+            Ok(x) => x,
+            Err(e) => {
+                godot_warn!("Discarding egui state from '{path}': {e}");
+                return;
+            }
+        };
+
+        self.share.egui.memory_mut(|x| *x = state.memory);
+        *self.persisted_viewports.borrow_mut() = state.viewports.into_iter().collect();
+    }
+
+    /// Commands to restore persisted window state of given viewport.
+    fn persisted_viewport_commands(&self, id: ViewportId) -> Vec<egui::ViewportCommand> {
+        let Some(saved) = self.persisted_viewports.borrow_mut().remove(&id) else {
+            return Vec::new();
+        };
+
+        let rect = Self::clamp_to_screens(saved.outer_rect);
+        let mut commands = vec![
+            egui::ViewportCommand::OuterPosition(rect.min),
+            egui::ViewportCommand::InnerSize(saved.inner_size.min(rect.size())),
+        ];
+
+        if saved.maximized {
+            commands.push(egui::ViewportCommand::Maximized(true));
+        }
+
+        if !saved.visible {
+            commands.push(egui::ViewportCommand::Visible(false));
+        }
+
+        commands
+    }
+
+    /// Move given rect into the primary screen, if it's not on any of existing screens.
+    fn clamp_to_screens(rect: egui::Rect) -> egui::Rect {
+        let gd_ds = DisplayServer::singleton();
+        let screen_rect = |screen: i32| {
+            egui::Rect::from_min_size(
+                gd_ds
+                    .screen_get_position_ex()
+                    .screen(screen)
+                    .done()
+                    .to_alternative(),
+                gd_ds
+                    .screen_get_size_ex()
+                    .screen(screen)
+                    .done()
+                    .to_counterpart(),
+            )
+        };
+
+        if (0..gd_ds.get_screen_count()).any(|x| screen_rect(x).contains(rect.center())) {
+            return rect;
+        }
+
+        let screen = screen_rect(gd_ds.get_primary_screen());
+        let size = rect.size().min(screen.size());
+        let min = rect.min.clamp(screen.min, screen.max - size);
+
+        egui::Rect::from_min_size(min, size)
+    }
+}
+
+/* ------------------------------------------ Privates ------------------------------------------ */
+
+/// Private API implementations
+impl EguiBridge {
     fn try_initiate(&self) {
         if self.tx_bg_task.borrow().is_some() {
             // It's already initiated.
@@ -497,6 +1839,12 @@ impl EguiBridge {
             assert!(self.rx_bg_task.replace(Some(rx_b)).is_none());
         };
 
+        let id = self.base().instance_id();
+        LIVE_BRIDGES.with_borrow_mut(|x| x.push((id, self.share.egui.clone())));
+
+        // Shared by every bridge; see `render_immediate_viewport`.
+        egui::Context::set_immediate_viewport_renderer(render_immediate_viewport);
+
         // Setup egui context & repaint callback.
         (&self.share.egui).pipe(|ctx| {
             let w_share = Arc::downgrade(&self.share);
@@ -515,6 +1863,18 @@ impl EguiBridge {
                 }
             });
         });
+
+        self.load_persistence();
+
+        if let Some(path) = self.resolved_dock_layout_path() {
+            self.widgets.dock.load_layout(&path);
+        }
+
+        if !self.input_overrides_applied.replace(true) && !Engine::singleton().is_editor_hint() {
+            if let Some(path) = self.resolved_input_overrides_path() {
+                apply_input_overrides(&path);
+            }
+        }
     }
 
     fn handle_bg_message(&self) {
@@ -529,6 +1889,7 @@ impl EguiBridge {
                 DeferredCommand::RequestRepaint(viewport_id) => {
                     ctx.request_repaint_of(viewport_id);
                 }
+                DeferredCommand::Run(work) => work(self),
             }
         }
 
@@ -544,6 +1905,18 @@ impl EguiBridge {
 
         self.rx_bg_task.take();
 
+        let id = self.base().instance_id();
+        LIVE_BRIDGES.with_borrow_mut(|x| x.retain(|(x, _)| *x != id));
+
+        // Don't leave the cursor hidden or captured after egui is gone.
+        self.restore_cursor(None);
+
+        self.save_persistence();
+
+        if let Some(path) = self.resolved_dock_layout_path() {
+            self.widgets.dock.save_layout(&path);
+        }
+
         // XXX: Seems all these manual cleanup redundant; as they're all in the tree?
 
         // self.textures.clear();
@@ -556,52 +1929,59 @@ impl EguiBridge {
         // }
     }
 
+    /// Release every resource that refers the code of this library; windows, painters
+    /// with their canvas items, and every registered callback. The bridge starts over
+    /// from scratch on next frame.
+    fn teardown(&mut self) {
+        self.try_dispose();
+
+        for (_, surface) in take(self.surfaces.get_mut()) {
+            Self::release_surface(surface, true);
+        }
+
+        self.share.viewports.lock().clear();
+        self.share.spawned_viewports.lock().clear();
+        self.share.egui.set_request_repaint_callback(|_| {});
+
+        self.widget_callbacks_first.get_mut().clear();
+        self.widget_callbacks_last.get_mut().clear();
+        self.widget_keys.get_mut().clear();
+        self.viewport_build_started.get_mut().clear();
+        self.widgets = default();
+        self.console_commands = default();
+        self.input_capture = default();
+        self.setup_scripts.get_mut().clear();
+        self.screenshot_tasks.get_mut().clear();
+        self.guest_hosts.get_mut().clear();
+        self.textures.get_mut()._clear();
+    }
+
     fn try_start_frame(&self) {
         assert!(std::thread::current().id() == self.share.main_thread_id);
 
+        let tick = Engine::singleton().get_process_frames();
+
+        // A frame started while this bridge can't process (e.g. paused tree) is never
+        // finished by `process`; finish it here, to keep frame start / finish paired.
+        if self.share.is_in_frame()
+            && self.frame_started_at.get() < tick
+            && !self.base().can_process()
+        {
+            // Renew the tick first; callbacks may start a frame during finishing.
+            self.frame_started_at.set(tick);
+            self.finish_frame();
+        }
+
         // Only perform frame start when necessary.
         if !self.share.try_advance_frame() {
             return;
         }
 
+        self.frame_started_at.set(tick);
+
         // Just lazily initiate the system.
         self.try_initiate();
 
-        // Register immediate renderer for this frame.
-        let w_self = global::weakref(&self.to_gd().to_variant());
-
-        // TODO: Make this not to capture anything; instead let it retrieve required data
-        // from `ctx`
-        egui::Context::set_immediate_viewport_renderer(move |ctx, viewport| {
-            let Ok(this) = w_self.try_to::<Gd<WeakRef>>() else {
-                unreachable!();
-            };
-
-            let Ok(this) = this.get_ref().try_to::<Gd<Self>>() else {
-                // It's just expired.
-                return;
-            };
-
-            let this = this.bind();
-
-            let p_src = this.share.egui.input(|x| x as *const _);
-            let p_new = ctx.input(|x| x as *const _);
-
-            if p_src != p_new {
-                // Another EGUI runtime?
-                return;
-            }
-
-            this.viewport_validate(
-                viewport.ids.this,
-                Some((viewport.ids.parent, viewport.builder)),
-            );
-            this.viewport_start_frame(viewport.ids.this);
-
-            (viewport.viewport_ui_cb)(ctx);
-            this.viewport_end_frame(viewport.ids.this);
-        });
-
         // Gather global input information
         let share = self.share.clone();
         share
@@ -636,12 +2016,16 @@ impl EguiBridge {
             });
 
         // Before starting a frame, check if we can spawn separate windows for viewport.
-        self.share.egui.set_embed_viewports(
-            self.base()
-                .get_viewport()
-                .unwrap()
-                .is_embedding_subwindows(),
-        );
+        // Embedded Godot windows can stand in for native ones if requested.
+        let embedding = self
+            .base()
+            .get_viewport()
+            .unwrap()
+            .is_embedding_subwindows();
+
+        self.share
+            .egui
+            .set_embed_viewports(embedding && !self.embedded_subwindows);
 
         // Start root frame as normal.
         self.viewport_validate(egui::ViewportId::ROOT, None);
@@ -684,6 +2068,16 @@ impl EguiBridge {
 
         self.viewport_start_frame(egui::ViewportId::ROOT);
 
+        // Panels take their space first; any other widget is laid out over the rest.
+        let passthrough = self.widgets.start_frame_handle_widgets(&self.share.egui);
+
+        if let Some(root) = self.surfaces.borrow().get(&ViewportId::ROOT) {
+            root.painter
+                .clone()
+                .bind_mut()
+                .set_passthrough_rect(passthrough);
+        }
+
         // Call registered callbacks for start of the frames.
         self.invoke_registered_callbacks(true);
     }
@@ -702,17 +2096,37 @@ impl EguiBridge {
         // We release borrow here to make callbacks safely invoke
         // `register_render_callback_*` methods.
 
-        callbacks.retain_mut(|(_, cb)| !cb(&self.share.egui).disposed());
+        // Priority may have been changed through handles since the last frame.
+        callbacks.sort_by_key(|x| x.slot.priority.get());
+        callbacks.retain_mut(|x| {
+            if x.slot.disposed.get() {
+                return false;
+            }
+
+            if !x.slot.visible.get() {
+                return true;
+            }
+
+            let disposed = (x.show)(&self.share.egui).disposed();
+            x.slot.disposed.set(disposed);
+
+            !disposed
+        });
 
         let mut cbs = get_cb();
         let should_sort = !cbs.is_empty() && !callbacks.is_empty();
 
         if should_sort {
             cbs.extend(callbacks);
-            cbs.sort_by_key(|(p, ..)| *p);
+            cbs.sort_by_key(|x| x.slot.priority.get());
         } else {
             *cbs = callbacks;
         }
+
+        drop(cbs);
+        self.widget_keys
+            .borrow_mut()
+            .retain(|_, x| !x.disposed.get());
     }
 
     fn reset_root_region_sync(&self) {
@@ -729,7 +2143,7 @@ impl EguiBridge {
         self.root_region_sync.set(None);
     }
 
-    fn finish_frame(&mut self) {
+    fn finish_frame(&self) {
         let share = self.share.clone();
 
         /* ------------------------- Spawned Widget / Viewport Handling ------------------------- */
@@ -849,6 +2263,9 @@ impl EguiBridge {
                 hash_map::Entry::Vacant(_) => (),
             }
 
+            // Revert cursor state, if this viewport was the one that changed it.
+            self.restore_cursor(Some(id));
+
             // Painter should be freed first, then viewport.
             Self::free_surface(self.surfaces.borrow_mut().remove(&id));
 
@@ -922,12 +2339,30 @@ impl EguiBridge {
 
         /* -------------------------------------- Painting -------------------------------------- */
 
+        let mut textures = self.textures.borrow_mut();
+
         // Handle new textures from output.
         for (id, delta) in textures_created {
-            self.textures.update_texture(id, delta);
+            textures.update_texture(id, delta);
         }
 
+        // Font atlases are separated by the scale they're rasterized for.
+        share.viewports.lock().pipe(|mut viewports| {
+            for (ppp, delta) in viewports.values_mut().flat_map(|x| x.font_deltas.drain(..)) {
+                textures.update_font_atlas(ppp, delta);
+            }
+
+            let in_use = viewports
+                .values()
+                .map(|x| x.target_ui_scale.to_bits())
+                .collect::<HashSet<_>>();
+
+            textures.retain_font_atlases(|ppp| in_use.contains(&ppp.to_bits()));
+        });
+
         // Paint all viewports
+        let mut viewports_drawn = 0;
+
         for (id, mut paint) in self.surfaces.borrow_mut().clone() {
             let Some((primitives, ui_scale)) = self
                 .share
@@ -941,31 +2376,314 @@ impl EguiBridge {
                 continue;
             };
 
+            if !paint.painter.is_instance_valid() {
+                // Guest host was freed; will be cleaned up on next validation.
+                continue;
+            }
+
             paint
                 .painter
                 .bind_mut()
-                .draw(&self.textures, primitives, ui_scale);
+                .draw(&textures, primitives, ui_scale);
+
+            viewports_drawn += 1;
         }
 
         // Handle disposed textures from output.
         for id in textures_freed {
-            self.textures.free_texture(id);
+            textures.free_texture(id);
+        }
+
+        drop(textures);
+
+        // Every viewport drawn this frame is ready to be captured on the next frame.
+        for task in self.screenshot_tasks.borrow_mut().iter_mut() {
+            task.armed = true;
         }
 
         /* ---------------------------------------- Done. --------------------------------------- */
 
+        let canvas_items = self
+            .surfaces
+            .borrow()
+            .values()
+            .filter(|x| x.painter.is_instance_valid())
+            .map(|x| x.painter.bind().canvas_item_count())
+            .sum();
+
+        let stats = FrameStats {
+            canvas_items,
+            viewports_drawn,
+            ..self.frame_stats.take()
+        };
+
+        self.share
+            .egui
+            .data_mut(|x| x.insert_temp(FrameStats::id(), stats));
+
         // Finish this frame.
         self.share.finish_frame();
     }
 
     fn free_surface(x: Option<SurfaceContext>) {
-        if let Some(mut x) = x {
-            x.painter.queue_free();
+        if let Some(x) = x {
+            Self::release_surface(x, false);
+        }
+    }
+
+    /// Free the nodes of surface. If `immediate`, they're freed right now, instead of at
+    /// the end of the frame.
+    fn release_surface(mut x: SurfaceContext, immediate: bool) {
+        // Painter of guest viewport may be freed along with its host.
+        if x.painter.is_instance_valid() {
+            if immediate {
+                x.painter.free();
+            } else {
+                x.painter.queue_free();
+            }
+        }
 
-            if let Some(mut x) = x.window {
+        if let Some(mut x) = x.window {
+            if immediate {
+                x.free();
+            } else {
                 x.queue_free();
             }
         }
+
+        if let (Some(GuestHost::Window(mut host)), Some(cb)) = (x.guest, x.on_guest_close) {
+            if host.is_instance_valid() && host.is_connected("close_requested", &cb) {
+                host.disconnect("close_requested", &cb);
+            }
+        }
+    }
+
+    fn close_request_callable(viewport: &ViewportContext) -> Callable {
+        let close_req = viewport.close_request.clone();
+        let close_enabled = viewport.close_enabled.clone();
+
+        Callable::from_fn("SubscribeClose", move |_| {
+            if close_enabled.load(Relaxed) {
+                close_req.store(VIEWPORT_CLOSE_REQUESTED, Relaxed);
+            }
+
+            Ok(Variant::nil())
+        })
+    }
+
+    /// Offset from the space that given window is positioned in, to the screen space that
+    /// egui expects. Embedded windows are positioned relative to their embedder.
+    fn window_screen_offset(window: &Gd<classes::Window>) -> Vector2i {
+        if window.is_embedded() {
+            // Embedded windows share the window id of the native window they're drawn on.
+            DisplayServer::singleton()
+                .window_get_position_ex()
+                .window_id(window.get_window_id())
+                .done()
+        } else {
+            Vector2i::ZERO
+        }
+    }
+
+    /// Pixels-per-point of given screen, without user zoom.
+    fn screen_pixels_per_point(screen: i32) -> f32 {
+        let gd_ds = DisplayServer::singleton();
+        let scale = gd_ds.screen_get_scale_ex().screen(screen).done();
+
+        if scale != 1. || classes::Os::singleton().get_name().to_string() != "Windows" {
+            return scale;
+        }
+
+        // Windows doesn't report scale factor, but the DPI, where 96 is 100%. Snap it to
+        // the scale steps that the OS actually offers.
+        let dpi = gd_ds.screen_get_dpi_ex().screen(screen).done();
+        (dpi as f32 / 96. * 4.).round().max(4.) / 4.
+    }
+
+    /// Request repaint of viewports which moved to another screen, or whose scale has
+    /// changed since the last update.
+    fn poll_screen_changes(&self) {
+        let gd_ds = DisplayServer::singleton();
+        let zoom = self.ui_zoom.max(0.1);
+        let mut changed = Vec::new();
+
+        // Lock order: `share.viewports` -> `surfaces`
+        let viewports = self.share.viewports.lock();
+        let surfaces = self.surfaces.borrow();
+
+        for (id, viewport) in viewports.iter() {
+            let Some(gd_wnd) = surfaces
+                .get(id)
+                .filter(|x| x.painter.is_instance_valid())
+                .and_then(|x| x.window.clone().or_else(|| x.painter.get_window()))
+            else {
+                continue;
+            };
+
+            let screen = gd_ds
+                .window_get_current_screen_ex()
+                .window_id(gd_wnd.get_window_id())
+                .done();
+            let scale = Self::screen_pixels_per_point(screen) * zoom;
+
+            if viewport.screen != Some(screen)
+                || viewport.info.native_pixels_per_point != Some(scale)
+            {
+                changed.push(*id);
+            }
+        }
+
+        drop((surfaces, viewports));
+
+        for id in changed {
+            self.share.egui.request_repaint_of(id);
+        }
+    }
+
+    fn window_restore_from(window: &mut Gd<classes::Window>, modes: &[window::Mode]) {
+        if modes.contains(&window.get_mode()) {
+            window.set_mode(window::Mode::WINDOWED);
+        }
+    }
+
+    /// Set application icon. Godot only supports single icon for the whole application,
+    /// therefore this is shared between all viewports.
+    fn apply_icon(icon: Option<&egui::IconData>) {
+        let Some(icon) = icon.filter(|x| !x.is_empty()) else {
+            // There's no way to revert the icon to default one.
+            return;
+        };
+
+        let payload = PackedByteArray::from(icon.rgba.as_slice());
+        let Some(image) = classes::Image::create_from_data(
+            icon.width as _,
+            icon.height as _,
+            false,
+            classes::image::Format::RGBA8,
+            &payload,
+        ) else {
+            godot_error!("Failed to create icon image from data!");
+            return;
+        };
+
+        DisplayServer::singleton().set_icon(&image);
+    }
+
+    /// Apply cursor grab / visibility requested from viewport `id`. The mouse mode before
+    /// the very first override is recorded, to be restored later.
+    fn viewport_set_cursor(
+        &self,
+        id: ViewportId,
+        grab: Option<egui::CursorGrab>,
+        visible: Option<bool>,
+    ) {
+        use classes::input::MouseMode;
+
+        let mut gd_input = classes::Input::singleton();
+        let mut slot = self.cursor_override.borrow_mut();
+        let state = slot.get_or_insert_with(|| CursorOverride {
+            owner: id,
+            restore: gd_input.get_mouse_mode(),
+            grab: default(),
+            visible: true,
+        });
+
+        state.owner = id;
+        state.grab = grab.unwrap_or(state.grab);
+        state.visible = visible.unwrap_or(state.visible);
+
+        let mode = match (state.grab, state.visible) {
+            (egui::CursorGrab::None, true) => {
+                // Every override was turned off; simply revert to original state.
+                let state = slot.take().unwrap();
+                state.restore
+            }
+            (egui::CursorGrab::None, false) => MouseMode::HIDDEN,
+            (egui::CursorGrab::Confined, true) => MouseMode::CONFINED,
+            (egui::CursorGrab::Confined, false) => MouseMode::CONFINED_HIDDEN,
+            (egui::CursorGrab::Locked, _) => MouseMode::CAPTURED,
+        };
+
+        gd_input.set_mouse_mode(mode);
+    }
+
+    /// Resolve screenshot requests which were drawn on the last frame.
+    fn capture_screenshots(&self) {
+        let (ready, pending) = take(&mut *self.screenshot_tasks.borrow_mut())
+            .into_iter()
+            .partition::<Vec<_>, _>(|x| x.armed);
+
+        // Requests made during callbacks below will be appended after pending ones.
+        self.screenshot_tasks.borrow_mut().extend(pending);
+
+        for task in ready {
+            let Some(image) = self.viewport_capture_image(task.viewport) else {
+                godot_warn!(
+                    "Failed to capture screenshot of viewport {:?}",
+                    task.viewport
+                );
+                continue;
+            };
+
+            (task.deliver)(self, image);
+        }
+    }
+
+    /// Capture current image of the region that given viewport's painter occupies.
+    fn viewport_capture_image(&self, id: ViewportId) -> Option<Gd<classes::Image>> {
+        let painter = self.surfaces.borrow().get(&id)?.painter.clone();
+        let gd_viewport = painter.get_viewport()?;
+        let mut image = gd_viewport.get_texture()?.get_image()?;
+
+        // Root painter may occupy only a part of the window; Also the render target can
+        // be scaled from the logical size by the stretch settings.
+        let visible_size = gd_viewport.get_visible_rect().size;
+        let image_size = image.get_size().cast_float();
+        let scale = image_size / visible_size.coord_max(Vector2::ONE);
+
+        let rect = painter.get_global_rect();
+        let region = Rect2::new(rect.position * scale, rect.size * scale)
+            .intersection(Rect2::new(Vector2::ZERO, image_size))
+            .map(Rect2i::from_rect2)?;
+
+        if region.size != image.get_size() {
+            image = image.get_region(region)?;
+        }
+
+        image.convert(classes::image::Format::RGBA8);
+        Some(image)
+    }
+
+    fn deliver_screenshot_event(&self, id: ViewportId, image: Gd<classes::Image>) {
+        let size = [image.get_width() as usize, image.get_height() as usize];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, image.get_data().as_slice());
+
+        if let Some(vp) = self.share.viewports.lock().get_mut(&id) {
+            vp.pending_events.push(egui::Event::Screenshot {
+                viewport_id: id,
+                image: Arc::new(image),
+            });
+        } else {
+            // Viewport was closed before the capture.
+            return;
+        }
+
+        // Lock should be released before this; repaint callback touches viewports.
+        self.share.egui.request_repaint_of(id);
+    }
+
+    /// Revert cursor state overridden by viewport commands. If `id` is specified, the
+    /// state is reverted only when that viewport was the one who changed it.
+    fn restore_cursor(&self, id: Option<ViewportId>) {
+        let mut slot = self.cursor_override.borrow_mut();
+
+        if slot
+            .as_ref()
+            .is_some_and(|x| id.map_or(true, |id| x.owner == id))
+        {
+            let state = slot.take().unwrap();
+            classes::Input::singleton().set_mouse_mode(state.restore);
+        }
     }
 
     fn viewport_validate(
@@ -976,6 +2694,17 @@ impl EguiBridge {
         // Checkout painter
         let mut surface = with_drop(self.surfaces.borrow_mut().remove(&id), Self::free_surface);
 
+        // Host of guest viewport could be freed by its owner, without detaching.
+        let guest = self.guest_hosts.borrow().get(&id).cloned();
+        let guest = match guest {
+            Some(host) if !host.is_instance_valid() => {
+                godot_warn!("Host of viewport {id:?} was freed without detaching; closing it");
+                self.viewport_detach(id);
+                None
+            }
+            guest => guest,
+        };
+
         // Spawn context if viewport id not exist
         let mut should_rebuild = false;
         let mut viewport_lock = self.share.viewports.lock();
@@ -1030,11 +2759,13 @@ impl EguiBridge {
                     })
                     .unwrap_or_else(|| self.base().get_window().expect("not added in tree!"));
 
-                let (updates, _) = build_with_parent
+                let (mut updates, _) = build_with_parent
                     .map(|x| {
                         init.patch(x.1.tap_mut(|init| {
                             if init.position.is_none() {
-                                let pos = gd_wnd_parent.get_position().to_alternative();
+                                let pos = (gd_wnd_parent.get_position()
+                                    + Self::window_screen_offset(&gd_wnd_parent))
+                                .to_alternative();
                                 init.position = Some(pos + egui::vec2(25., 25.));
                             }
 
@@ -1045,20 +2776,34 @@ impl EguiBridge {
                     })
                     .unwrap_or_default();
 
+                // Window state from previous session overrides the defaults above.
+                if id != ViewportId::ROOT {
+                    updates.extend(self.persisted_viewport_commands(id));
+                }
+
                 entry.insert(ViewportContext {
                     repaint_at: Some(Instant::now()),
                     rx_update,
                     close_request: Default::default(),
+                    close_enabled: Arc::new(AtomicBool::new(true)),
                     builder: init,
                     target_ui_scale: 1.,
                     updates,
+                    pending_events: default(),
                     paint_this_frame: None,
                     info: default(),
+                    screen: None,
+                    font_deltas: default(),
                 })
             }
         };
 
-        if surface.is_none() || should_rebuild {
+        // Surface should follow the host change, or host gone.
+        let host_changed = surface
+            .iter()
+            .any(|x| x.guest != guest || !x.painter.is_instance_valid());
+
+        if surface.is_none() || should_rebuild || host_changed {
             drop(surface.take());
 
             // Create channel between new viewport and painter.
@@ -1067,6 +2812,10 @@ impl EguiBridge {
 
             // Rebuild UI.
             let mut gd_painter = surface::EguiViewportBridge::new_alloc();
+            gd_painter.bind_mut().tap_mut(|x| {
+                x.set_input_enabled(!self.input_frozen.get());
+                x.set_input_capture(self.input_capture.clone());
+            });
 
             let ctx = self.share.egui.clone();
             gd_painter.bind_mut().initiate(
@@ -1093,6 +2842,7 @@ impl EguiBridge {
                 }),
             );
 
+            let mut on_guest_close = None;
             let gd_wnd = if id == ViewportId::ROOT {
                 // Attach directly to this component.
                 self.to_gd().add_child(&gd_painter.clone());
@@ -1115,10 +2865,24 @@ impl EguiBridge {
                 // we need to consume the input inside egui, we rather make call to
                 // `Viewport::set_input_as_handled()` which consumes input even before
                 // reaching out to `gui_input()` callbacks of any.
-                gd_painter.set_mouse_filter(MouseFilter::IGNORE);
+                gd_painter.bind_mut().set_input_mode(InputMode::Intercept);
+
+                None
+            } else if let Some(host) = &guest {
+                // Guest host is shared with other nodes of the user, same as root.
+                let mode = match host {
+                    GuestHost::Overlay(_) => InputMode::Forwarded,
+                    _ => InputMode::Intercept,
+                };
+
+                gd_painter.bind_mut().set_input_mode(mode);
+                host.node().add_child(&gd_painter.clone());
 
-                // To do the tricks
-                gd_painter.set_process_input(true);
+                if let GuestHost::Window(mut gd_wnd) = host.clone() {
+                    let cb = Self::close_request_callable(viewport);
+                    gd_wnd.connect("close_requested", &cb);
+                    on_guest_close = Some(cb);
+                }
 
                 None
             } else {
@@ -1126,8 +2890,7 @@ impl EguiBridge {
 
                 // NOTE: For other viewports, they exclusively use the window, therefore
                 // don't need an `input` trick to work correctly.
-                gd_painter.set_mouse_filter(MouseFilter::PASS);
-                gd_painter.set_process_input(false);
+                gd_painter.bind_mut().set_input_mode(InputMode::Gui);
 
                 // Spawn additional window to hold painter.
                 let mut gd_wnd = classes::Window::new_alloc();
@@ -1139,14 +2902,7 @@ impl EguiBridge {
                 gd_painter.set_owner(&gd_wnd.clone());
 
                 // Bind window close request.
-                let close_req = viewport.close_request.clone();
-                gd_wnd.connect(
-                    "close_requested",
-                    &Callable::from_fn("SubscribeClose", move |_| {
-                        close_req.store(VIEWPORT_CLOSE_REQUESTED, Relaxed);
-                        Ok(Variant::nil())
-                    }),
-                );
+                gd_wnd.connect("close_requested", &Self::close_request_callable(viewport));
 
                 // NOTE: List of recreation-only flags
                 // - active
@@ -1170,12 +2926,18 @@ impl EguiBridge {
                     gd_wnd.set_flag(Flags::BORDERLESS, true);
                 }
 
+                if let Some(enabled) = builder.close_button {
+                    viewport.close_enabled.store(enabled, Relaxed);
+                }
+
                 Some(gd_wnd)
             };
 
             *surface = Some(SurfaceContext {
                 painter: gd_painter,
                 window: gd_wnd,
+                guest,
+                on_guest_close,
             });
         }
 
@@ -1183,11 +2945,68 @@ impl EguiBridge {
             unreachable!()
         };
 
+        // The window which actually hosts this viewport. For root viewport, it's the
+        // window that this bridge belongs to.
+        let gd_host_wnd = surface
+            .window
+            .clone()
+            .or_else(|| surface.painter.get_window());
+
         for command in viewport.updates.drain(..) {
             use egui::ViewportCommand::*;
 
+            // Commands that don't touch the window itself; these are valid for root
+            // viewport either.
+            let command = match command {
+                Icon(icon) => {
+                    Self::apply_icon(icon.as_deref());
+                    continue;
+                }
+                RequestUserAttention(kind) => {
+                    // There's no explicit way to cancel the attention request; the
+                    // platform resets it once the window gets focused.
+                    if kind != egui::UserAttentionType::Reset {
+                        if let Some(wnd) = &gd_host_wnd {
+                            DisplayServer::singleton()
+                                .window_request_attention_ex()
+                                .window_id(wnd.get_window_id())
+                                .done();
+                        }
+                    }
+
+                    continue;
+                }
+                CursorPosition(pos) => {
+                    // NOTE: `Input::warp_mouse` is relative to the focused window, which
+                    // may not be the window of this viewport. Warping relative to the
+                    // painter handles both root region offset and the ui scale.
+                    let pos = pos.to_vec2() * viewport.target_ui_scale;
+                    surface.painter.clone().warp_mouse(pos.to_counterpart());
+                    continue;
+                }
+                CursorGrab(grab) => {
+                    self.viewport_set_cursor(id, Some(grab), None);
+                    continue;
+                }
+                CursorVisible(visible) => {
+                    self.viewport_set_cursor(id, None, Some(visible));
+                    continue;
+                }
+                Screenshot => {
+                    self.screenshot_tasks.borrow_mut().push(ScreenshotTask {
+                        viewport: id,
+                        armed: false,
+                        deliver: Box::new(move |this, image| {
+                            this.deliver_screenshot_event(id, image);
+                        }),
+                    });
+                    continue;
+                }
+                command => command,
+            };
+
             let Some(mut window) = surface.window.clone() else {
-                // Root viewport won't receive any viewport commands.
+                // Root viewport won't receive any other viewport commands.
                 continue;
             };
 
@@ -1221,7 +3040,10 @@ impl EguiBridge {
                     // Set viewport.dragging = true; then until it finishes dragging, get
                     // mouse delta then move the window.
                 }
-                OuterPosition(pos) => window.set_position(pos.to_alternative()),
+                OuterPosition(pos) => {
+                    let offset = Self::window_screen_offset(&window);
+                    window.set_position(pos.to_alternative() - offset);
+                }
 
                 // FIXME: Change painter size; not the containing window size.
                 InnerSize(size) => window.set_size(size.to_alternative()),
@@ -1237,13 +3059,27 @@ impl EguiBridge {
                     // TODO: Implement this
                 }
                 Resizable(value) => window.set_flag(window::Flags::RESIZE_DISABLED, !value),
-                EnableButtons { .. } => {}
+                EnableButtons { close, .. } => {
+                    // Godot doesn't expose minimize / maximize buttons separately; those
+                    // follow `Resizable` flag of the window.
+                    viewport.close_enabled.store(close, Relaxed);
+                }
                 Minimized(true) => window.set_mode(window::Mode::MINIMIZED),
-                Minimized(_) => {}
                 Maximized(true) => window.set_mode(window::Mode::MAXIMIZED),
-                Maximized(_) => {}
                 Fullscreen(true) => window.set_mode(window::Mode::FULLSCREEN),
-                Fullscreen(_) => {}
+
+                // Only restore the window when it's actually in the state being turned
+                // off; e.g. `Minimized(false)` should not un-maximize the window.
+                Minimized(false) => {
+                    Self::window_restore_from(&mut window, &[window::Mode::MINIMIZED])
+                }
+                Maximized(false) => {
+                    Self::window_restore_from(&mut window, &[window::Mode::MAXIMIZED])
+                }
+                Fullscreen(false) => Self::window_restore_from(
+                    &mut window,
+                    &[window::Mode::FULLSCREEN, window::Mode::EXCLUSIVE_FULLSCREEN],
+                ),
                 Decorations(deco) => window.set_flag(window::Flags::BORDERLESS, !deco),
                 WindowLevel(level) => {
                     let enabled = match level {
@@ -1253,9 +3089,6 @@ impl EguiBridge {
 
                     window.set_flag(window::Flags::ALWAYS_ON_TOP, enabled);
                 }
-                Icon(_) => {
-                    // TODO: Find way to handle this.
-                }
                 IMERect(rect) => {
                     window.set_ime_position(rect.to_alternative().position);
                 }
@@ -1268,25 +3101,19 @@ impl EguiBridge {
                 Focus => {
                     window.grab_focus();
                 }
-                RequestUserAttention(_) => {
-                    // No way?
-                }
                 SetTheme(_) => {
                     // How?
                 }
                 ContentProtected(_) => {}
-                CursorPosition(_pos) => {}
-                CursorGrab(_) => {}
-                CursorVisible(_) => {
-                    // TODO: How can we achieve this in safe manner?
-                    // - e.g. If user simply disposed EGUI after hiding cursor...
-                }
                 MousePassthrough(enabled) => {
                     window.set_flag(window::Flags::MOUSE_PASSTHROUGH, enabled);
                 }
-                Screenshot => {
-                    // TODO: How?
-                }
+                Icon(_)
+                | RequestUserAttention(_)
+                | CursorPosition(_)
+                | CursorGrab(_)
+                | CursorVisible(_)
+                | Screenshot => unreachable!("handled above"),
             }
         }
 
@@ -1308,9 +3135,8 @@ impl EguiBridge {
                 }
             };
 
-            let info = &mut viewport.info;
-
-            let inner_pos = gd_wnd.get_position().cast_float() + surface.painter.get_position();
+            let outer_pos = gd_wnd.get_position() + Self::window_screen_offset(&gd_wnd);
+            let inner_pos = outer_pos.cast_float() + surface.painter.get_position();
             let inner_size = surface.painter.get_size();
 
             let gd_ds = DisplayServer::singleton();
@@ -1318,7 +3144,10 @@ impl EguiBridge {
                 .window_get_current_screen_ex()
                 .window_id(gd_wnd.get_window_id())
                 .done();
-            let scale = gd_ds.screen_get_scale_ex().screen(id_screen).done();
+            let scale = Self::screen_pixels_per_point(id_screen) * self.ui_zoom.max(0.1);
+            viewport.screen = Some(id_screen);
+
+            let info = &mut viewport.info;
 
             info.inner_rect = Some(Rect2::new(inner_pos, inner_size).to_counterpart());
             info.focused = Some(gd_wnd.has_focus());
@@ -1334,7 +3163,7 @@ impl EguiBridge {
                     .to_counterpart(),
             );
             info.outer_rect = Some(egui::Rect::from_min_size(
-                gd_wnd.get_position().to_alternative(),
+                outer_pos.to_alternative(),
                 gd_wnd.get_size().to_counterpart(),
             ));
         }
@@ -1365,6 +3194,9 @@ impl EguiBridge {
             let mut viewport = self.share.viewports.lock();
             let viewport = viewport.get_mut(&id).unwrap();
 
+            // Pending events are older than the ones still in the channel; see
+            // `has_pending_update`.
+            raw_input.events.append(&mut viewport.pending_events);
             raw_input.events.extend(viewport.rx_update.try_iter());
             raw_input.screen_rect = viewport.info.inner_rect.map(|x| {
                 egui::Rect::from_min_size(egui::Pos2::ZERO, x.size() / viewport.target_ui_scale)
@@ -1373,8 +3205,8 @@ impl EguiBridge {
             raw_input.focused = viewport.info.focused.unwrap_or_default();
             raw_input.viewport_id = id;
 
-            // Just set repaint schedule to far future.
-            viewport.repaint_at = Some(Instant::now() + Duration::from_secs(3600));
+            // Nothing is scheduled until egui requests repaint during this frame.
+            viewport.repaint_at = None;
 
             // If close request is delivered from platform, forward the event to EGUI that
             // allow user logic to handle this. (e.g. cancel the close request)
@@ -1391,6 +3223,9 @@ impl EguiBridge {
             }
         }
 
+        self.viewport_build_started
+            .borrow_mut()
+            .push(Instant::now());
         self.share.egui.begin_frame(raw_input);
     }
 
@@ -1398,10 +3233,31 @@ impl EguiBridge {
         // Retrieve viewport-wise output.
         let mut output = self.share.egui.end_frame();
 
+        let mut stats = self.frame_stats.get();
+        let mut started = self.viewport_build_started.borrow_mut();
+
+        // Nested viewports are already counted in the outer one.
+        if let Some(at) = started.pop().filter(|_| started.is_empty()) {
+            stats.build_time += at.elapsed();
+        }
+
+        drop(started);
+
         let paints = take(&mut output.shapes);
         let ppi = output.pixels_per_point;
 
+        // Font atlas of this viewport is rasterized for its own pixels-per-point, but it's
+        // uploaded as the default texture anyways. Keep it apart from the other textures.
+        let (font_deltas, textures) = take(&mut output.textures_delta.set)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(tex_id, _)| *tex_id == egui::TextureId::default());
+        output.textures_delta.set = textures;
+
+        let tessellation_started = Instant::now();
         let primitives = self.share.egui.tessellate(paints, ppi);
+
+        stats.tessellation_time += tessellation_started.elapsed();
+        self.frame_stats.set(stats);
         self.share
             .viewports
             .lock()
@@ -1410,21 +3266,26 @@ impl EguiBridge {
             .pipe(|vp| {
                 vp.paint_this_frame = Some(primitives);
                 vp.target_ui_scale = ppi;
+                vp.font_deltas
+                    .extend(font_deltas.into_iter().map(|(_, delta)| (ppi, delta)));
             });
 
-        let mut gd_wnd = self
+        // Painter of guest viewport may be already gone along with its host.
+        let gd_wnd = self
             .surfaces
             .borrow_mut()
             .get(&id)
-            .and_then(|x| x.painter.get_window())
-            .expect("A painter should be spawned under any valid window!");
-
-        if let Some(ime) = output.platform_output.ime.take() {
-            // XXX: Is calling this every frame safe?
-            gd_wnd.set_ime_active(true);
-            gd_wnd.set_ime_position(ime.cursor_rect.min.to_alternative());
-        } else {
-            gd_wnd.set_ime_active(false);
+            .filter(|x| x.painter.is_instance_valid())
+            .and_then(|x| x.painter.get_window());
+
+        if let Some(mut gd_wnd) = gd_wnd {
+            if let Some(ime) = output.platform_output.ime.take() {
+                // XXX: Is calling this every frame safe?
+                gd_wnd.set_ime_active(true);
+                gd_wnd.set_ime_position(ime.cursor_rect.min.to_alternative());
+            } else {
+                gd_wnd.set_ime_active(false);
+            }
         }
 
         // Handle platform outputs accumulated from all viewports.
@@ -1480,6 +3341,87 @@ impl EguiBridge {
         }
     }
 
+    fn poll_menu_bar_toggle(&self) {
+        let action = &self.menu_bar_toggle_action;
+
+        if action.is_empty() || !classes::InputMap::singleton().has_action(action) {
+            return;
+        }
+
+        // Don't toggle while typing into egui.
+        if self.share.egui.wants_keyboard_input() {
+            return;
+        }
+
+        if classes::Input::singleton().is_action_just_pressed(action) {
+            self.set_menu_bar_visible(!self.is_menu_bar_visible());
+        }
+    }
+
+    fn poll_wake_actions(&self) {
+        let input_map = classes::InputMap::singleton();
+        let input = classes::Input::singleton();
+
+        let pressed = WakeActions::load(&self.share.egui)
+            .into_iter()
+            .map(|x| StringName::from(x.as_str()))
+            .any(|x| input_map.has_action(&x) && input.is_action_just_pressed(&x));
+
+        if pressed {
+            self.share.repaint_queued.store(true, Relaxed);
+        }
+    }
+
+    fn apply_pause_policy(&mut self) {
+        let mode = match self.pause_policy {
+            PausePolicy::Inherit => match self.overridden_process_mode.take() {
+                Some(mode) => mode,
+                None => return,
+            },
+            PausePolicy::AlwaysRun | PausePolicy::FreezeInput => ProcessMode::ALWAYS,
+            PausePolicy::PauseWithTree => ProcessMode::PAUSABLE,
+        };
+
+        if self.pause_policy != PausePolicy::Inherit && self.overridden_process_mode.is_none() {
+            self.overridden_process_mode = Some(self.base().get_process_mode());
+        }
+
+        // Painters inherit this, therefore they receive input under the same policy.
+        self.base_mut().set_process_mode(mode);
+    }
+
+    /// Enable / disable input of every painter, according to [`PausePolicy::FreezeInput`].
+    fn update_input_freeze(&self) {
+        let frozen = self.pause_policy == PausePolicy::FreezeInput
+            && self.base().get_tree().is_some_and(|x| x.is_paused());
+
+        if self.input_frozen.replace(frozen) == frozen {
+            return;
+        }
+
+        for surface in self.surfaces.borrow().values() {
+            if surface.painter.is_instance_valid() {
+                surface
+                    .painter
+                    .clone()
+                    .bind_mut()
+                    .set_input_enabled(!frozen);
+            }
+        }
+    }
+
+    /// Whether any viewport has pending input, or a delayed repaint which came due.
+    fn has_pending_update(&self) -> bool {
+        let now = Instant::now();
+
+        self.share.viewports.lock().values_mut().any(|vp| {
+            // Keep received inputs until the next frame starts.
+            vp.pending_events.extend(vp.rx_update.try_iter());
+
+            !vp.pending_events.is_empty() || vp.repaint_at.is_some_and(|x| x <= now)
+        })
+    }
+
     /// Start frame in thread-safe manner.
     fn queue_try_start_frame(&self) {
         if std::thread::current().id() == self.share.main_thread_id {
@@ -1501,8 +3443,16 @@ impl EguiBridge {
 impl SharedContext {
     fn repaint(&self, info: egui::RequestRepaintInfo) {
         if let Some(x) = self.viewports.lock().get_mut(&info.viewport_id) {
-            x.repaint_at = Some(Instant::now() + info.delay);
-            self.repaint_queued.store(true, Relaxed);
+            // Unreachable points of time are simply ignored.
+            if let Some(at) = Instant::now().checked_add(info.delay) {
+                x.repaint_at = Some(x.repaint_at.map_or(at, |prev| prev.min(at)));
+            }
+
+            // Delayed ones are picked up by the timer in reactive mode; see
+            // `has_pending_update`.
+            if info.delay.is_zero() || !self.reactive.load(Relaxed) {
+                self.repaint_queued.store(true, Relaxed);
+            }
         } else {
             godot_warn!("EGUI requested repaint for unregistered viewpot: {info:?}")
         };
@@ -1567,7 +3517,7 @@ impl CheckExpired for std::sync::Arc<AtomicBool> {
 
 impl<T: GodotClass> CheckExpired for Gd<T> {
     fn expired(&self) -> bool {
-        self.is_instance_valid()
+        !self.is_instance_valid()
     }
 }
 
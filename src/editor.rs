@@ -0,0 +1,657 @@
+//! Editor integrations.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use egui::{ViewportBuilder, ViewportId};
+use godot::{
+    classes::{
+        self, editor_plugin::DockSlot, notify::ControlNotification, Button, Control,
+        EditorInspectorPlugin, EditorInterface, EditorPlugin, EditorProperty, IControl,
+        IEditorInspectorPlugin, IEditorProperty, SubViewport, SubViewportContainer,
+    },
+    global::{PropertyHint, PropertyUsageFlags},
+    prelude::*,
+};
+use tap::prelude::Tap;
+
+use crate::{context::FnEguiDrawExt, EguiBridge};
+
+/* --------------------------------------- Inspector Plugin -------------------------------------- */
+
+/// Properties that an egui property editor is registered for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InspectorTarget {
+    /// Every property of given class, including inherited classes.
+    Class(String),
+
+    /// A single property of given class, including inherited classes.
+    Property { class: String, property: String },
+
+    /// Every property exported with given hint.
+    Hint(PropertyHint),
+}
+
+impl InspectorTarget {
+    fn matches(&self, object: &Gd<Object>, property: &str, hint: PropertyHint) -> bool {
+        match self {
+            Self::Class(class) => object.is_class(class),
+            Self::Property {
+                class,
+                property: name,
+            } => name == property && object.is_class(class),
+            Self::Hint(x) => *x == hint,
+        }
+    }
+
+    fn may_handle(&self, object: &Gd<Object>) -> bool {
+        match self {
+            Self::Class(class) | Self::Property { class, .. } => object.is_class(class),
+            Self::Hint(_) => true,
+        }
+    }
+}
+
+/// Draws the editor of a property. Changes made on the value are committed to the edited
+/// object through [`EditorProperty::emit_changed`], therefore undo/redo works.
+pub type FnPropertyDraw = dyn FnMut(&mut egui::Ui, &mut Variant) + 'static;
+
+/// An [`EditorInspectorPlugin`] which draws the registered properties with egui.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::editor::*;
+/// let mut plugin = EguiInspectorPlugin::new_gd();
+/// plugin.bind_mut().register(
+///     InspectorTarget::Property {
+///         class: "Node2D".into(),
+///         property: "rotation".into(),
+///     },
+///     |ui, value| {
+///         let mut rad = value.to::<f32>();
+///         ui.drag_angle(&mut rad);
+///         *value = rad.to_variant();
+///     },
+/// );
+///
+/// // Then, from the `EditorPlugin` ...
+/// // self.base_mut().add_inspector_plugin(plugin.upcast());
+/// ```
+#[derive(GodotClass)]
+#[class(base=EditorInspectorPlugin, tool, init, rename=GodotEguiInspectorPlugin)]
+pub struct EguiInspectorPlugin {
+    base: Base<EditorInspectorPlugin>,
+
+    /// Registered editors. Earlier registration takes precedence.
+    editors: Vec<(InspectorTarget, Rc<RefCell<Box<FnPropertyDraw>>>)>,
+
+    /// Bridge that renders every property editor of this plugin. Spawned lazily.
+    bridge: Option<Gd<EguiBridge>>,
+}
+
+#[godot_api]
+impl IEditorInspectorPlugin for EguiInspectorPlugin {
+    fn can_handle(&self, object: Gd<Object>) -> bool {
+        self.editors.iter().any(|(x, _)| x.may_handle(&object))
+    }
+
+    fn parse_property(
+        &mut self,
+        object: Gd<Object>,
+        _type: VariantType,
+        name: GString,
+        hint_type: PropertyHint,
+        _hint_string: GString,
+        _usage_flags: PropertyUsageFlags,
+        _wide: bool,
+    ) -> bool {
+        let name = name.to_string();
+        let Some(draw) = self
+            .editors
+            .iter()
+            .find(|(x, _)| x.matches(&object, &name, hint_type))
+            .map(|(_, draw)| draw.clone())
+        else {
+            return false;
+        };
+
+        let editor = EguiEditorProperty::create(self.bridge(), draw);
+        self.base_mut().add_property_editor(&name, &editor);
+
+        true
+    }
+}
+
+impl EguiInspectorPlugin {
+    /// Register an egui editor for given properties.
+    pub fn register(
+        &mut self,
+        target: InspectorTarget,
+        draw: impl FnMut(&mut egui::Ui, &mut Variant) + 'static,
+    ) {
+        self.editors
+            .push((target, Rc::new(RefCell::new(Box::new(draw)))));
+    }
+
+    fn bridge(&mut self) -> Gd<EguiBridge> {
+        if let Some(bridge) = self.bridge.as_ref().filter(|x| x.is_instance_valid()) {
+            return bridge.clone();
+        }
+
+        let edt = EditorInterface::singleton();
+        let mut bridge = EguiBridge::new_alloc();
+
+        // Follow editor's display scale.
+        bridge.bind_mut().ui_zoom = edt.get_editor_scale();
+
+        let mut base = edt.get_base_control().expect("editor is not ready!");
+        base.add_child(&bridge.clone());
+
+        self.bridge = Some(bridge.clone());
+        bridge
+    }
+}
+
+impl Drop for EguiInspectorPlugin {
+    fn drop(&mut self) {
+        if let Some(mut bridge) = self.bridge.take().filter(|x| x.is_instance_valid()) {
+            bridge.queue_free();
+        }
+    }
+}
+
+/* --------------------------------------- Property Editor --------------------------------------- */
+
+/// Hosts an egui viewport inside the inspector, through guest mode.
+#[derive(GodotClass)]
+#[class(base=EditorProperty, tool, init, hidden, rename=INTERNAL__GodotEguiEditorProperty)]
+struct EguiEditorProperty {
+    base: Base<EditorProperty>,
+
+    bridge: Option<Gd<EguiBridge>>,
+    draw: Option<Rc<RefCell<Box<FnPropertyDraw>>>>,
+    container: Option<Gd<SubViewportContainer>>,
+
+    /// State shared with the viewport.
+    state: Rc<PropertyState>,
+}
+
+#[derive(Default)]
+struct PropertyState {
+    value: RefCell<Variant>,
+    read_only: Cell<bool>,
+
+    /// Set by viewport when the value is modified by the user.
+    changed: Cell<bool>,
+
+    /// Content height in pixels, measured on last frame.
+    height: Cell<f32>,
+}
+
+#[godot_api]
+impl IEditorProperty for EguiEditorProperty {
+    fn ready(&mut self) {
+        let (Some(bridge), Some(draw)) = (self.bridge.clone(), self.draw.clone()) else {
+            return;
+        };
+
+        let mut container = SubViewportContainer::new_alloc();
+        container.set_stretch(true);
+        container.set_custom_minimum_size(Vector2::new(0., 24.));
+
+        let mut host = SubViewport::new_alloc();
+        host.set_transparent_background(true);
+
+        container.add_child(&host.clone());
+        self.base_mut().add_child(&container.clone());
+        self.base_mut().set_process(true);
+        self.container = Some(container);
+
+        let id = self.viewport_id();
+        let state = self.state.clone();
+        let bridge = bridge.bind();
+
+        bridge.viewport_attach_to_subviewport(id, host);
+        bridge.viewport_spawn(
+            id,
+            ViewportBuilder::default(),
+            (move |ctx: &egui::Context| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::none().inner_margin(2.))
+                    .show(ctx, |ui| {
+                        ui.add_enabled_ui(!state.read_only.get(), |ui| {
+                            let mut value = state.value.borrow_mut();
+                            let prev = value.clone();
+
+                            draw.borrow_mut()(ui, &mut value);
+
+                            if *value != prev {
+                                state.changed.set(true);
+                            }
+                        });
+
+                        let height = ui.min_rect().height() + 4.;
+                        state.height.set(height * ctx.pixels_per_point());
+                    });
+            })
+            .bind(self.to_gd()),
+        );
+    }
+
+    fn process(&mut self, _dt: f64) {
+        if self.state.changed.take() {
+            let value = self.state.value.borrow().clone();
+            let property = self.base().get_edited_property();
+
+            self.base_mut().emit_changed(&property, &value);
+        }
+
+        // Follow the height of egui content.
+        let height = self.state.height.get().ceil().max(24.);
+        if let Some(container) = self.container.as_mut() {
+            if container.get_custom_minimum_size().y != height {
+                container.set_custom_minimum_size(Vector2::new(0., height));
+            }
+        }
+    }
+
+    fn update_property(&mut self) {
+        let Some(object) = self.base().get_edited_object() else {
+            return;
+        };
+
+        let value = object.get(&self.base().get_edited_property());
+        self.state.read_only.set(self.base().is_read_only());
+
+        if *self.state.value.borrow() == value {
+            return;
+        }
+
+        *self.state.value.borrow_mut() = value;
+
+        if let Some(bridge) = self.bridge.as_ref() {
+            let id = self.viewport_id();
+            bridge
+                .bind()
+                .setup_context(move |ctx| ctx.request_repaint_of(id));
+        }
+    }
+
+    fn exit_tree(&mut self) {
+        if let Some(bridge) = self.bridge.as_ref().filter(|x| x.is_instance_valid()) {
+            bridge.bind().viewport_detach(self.viewport_id());
+        }
+    }
+}
+
+impl EguiEditorProperty {
+    fn create(bridge: Gd<EguiBridge>, draw: Rc<RefCell<Box<FnPropertyDraw>>>) -> Gd<Self> {
+        let mut this = Self::new_alloc();
+
+        {
+            let mut x = this.bind_mut();
+            x.bridge = Some(bridge);
+            x.draw = Some(draw);
+        }
+
+        this
+    }
+
+    fn viewport_id(&self) -> ViewportId {
+        ViewportId::from_hash_of(self.base().instance_id())
+    }
+}
+
+/* ---------------------------------------- Editor Panels ---------------------------------------- */
+
+/// An editor dock, which draws its content with egui.
+///
+/// ```no_run
+/// # use godot::{prelude::*, classes::{EditorPlugin, editor_plugin::DockSlot}};
+/// # use gdext_egui::editor::*;
+/// # let plugin: Gd<EditorPlugin> = unimplemented!();
+/// let dock = EguiEditorDock::new(DockSlot::RIGHT_UL, "My Dock", |ui| {
+///     ui.label("Hello, editor!");
+/// });
+///
+/// // Removed and freed automatically when the plugin exits the tree.
+/// EguiEditorDock::install(&dock, plugin);
+/// ```
+#[derive(GodotClass)]
+#[class(base=Control, tool, init, rename=GodotEguiEditorDock)]
+pub struct EguiEditorDock {
+    base: Base<Control>,
+
+    #[init(val = DockSlot::RIGHT_UL)]
+    slot: DockSlot,
+
+    core: PanelCore,
+}
+
+#[godot_api]
+impl IControl for EguiEditorDock {
+    fn ready(&mut self) {
+        let owner = self.to_gd().upcast();
+        self.core.ready(owner);
+    }
+
+    fn on_notification(&mut self, what: ControlNotification) {
+        let owner = self.to_gd().upcast();
+        self.core.on_notification(owner, what);
+    }
+}
+
+impl EguiEditorDock {
+    pub fn new(slot: DockSlot, title: &str, draw: impl FnMut(&mut egui::Ui) + 'static) -> Gd<Self> {
+        let mut this = Self::new_alloc();
+        this.set_name(title); // Dock tab shows the node name.
+
+        {
+            let mut x = this.bind_mut();
+            x.slot = slot;
+            x.core.draw = Some(Box::new(draw));
+        }
+
+        this
+    }
+
+    /// Add the dock to the editor. It's removed and freed when the plugin exits the tree;
+    /// e.g. when the plugin is disabled, or the library is reloaded.
+    pub fn install(this: &Gd<Self>, mut plugin: Gd<EditorPlugin>) {
+        let slot = this.bind().slot;
+        plugin.add_control_to_dock(slot, this);
+
+        remove_on_plugin_exit(&mut plugin, this.clone().upcast(), |plugin, panel| {
+            plugin.remove_control_from_docks(panel);
+        });
+    }
+}
+
+/// An editor bottom panel, which draws its content with egui.
+///
+/// See [`EguiEditorDock`] for usage.
+#[derive(GodotClass)]
+#[class(base=Control, tool, init, rename=GodotEguiEditorBottomPanel)]
+pub struct EguiEditorBottomPanel {
+    base: Base<Control>,
+    title: GString,
+    core: PanelCore,
+}
+
+#[godot_api]
+impl IControl for EguiEditorBottomPanel {
+    fn ready(&mut self) {
+        let owner = self.to_gd().upcast();
+        self.core.ready(owner);
+    }
+
+    fn on_notification(&mut self, what: ControlNotification) {
+        let owner = self.to_gd().upcast();
+        self.core.on_notification(owner, what);
+    }
+}
+
+impl EguiEditorBottomPanel {
+    pub fn new(title: &str, draw: impl FnMut(&mut egui::Ui) + 'static) -> Gd<Self> {
+        let mut this = Self::new_alloc();
+
+        // Bottom panel doesn't give any height unless requested.
+        this.set_custom_minimum_size(Vector2::new(0., 200.));
+
+        {
+            let mut x = this.bind_mut();
+            x.title = title.into();
+            x.core.draw = Some(Box::new(draw));
+        }
+
+        this
+    }
+
+    /// Add the panel to the editor, and returns the button that toggles it. It's removed
+    /// and freed when the plugin exits the tree.
+    pub fn install(this: &Gd<Self>, mut plugin: Gd<EditorPlugin>) -> Option<Gd<Button>> {
+        let title = this.bind().title.clone();
+        let button = plugin.add_control_to_bottom_panel(this, &title);
+
+        remove_on_plugin_exit(&mut plugin, this.clone().upcast(), |plugin, panel| {
+            plugin.remove_control_from_bottom_panel(panel);
+        });
+
+        button
+    }
+}
+
+/// Common part of the editor panels; a bridge whose root region follows the panel.
+#[derive(Default)]
+struct PanelCore {
+    /// Moved into the render callback once the panel is ready.
+    draw: Option<Box<dyn FnMut(&mut egui::Ui)>>,
+
+    bridge: Option<Gd<EguiBridge>>,
+}
+
+impl PanelCore {
+    fn ready(&mut self, mut owner: Gd<Control>) {
+        let Some(mut draw) = self.draw.take() else {
+            // Already initialized; e.g. dock moved to another slot.
+            return;
+        };
+
+        let mut bridge = EguiBridge::new_alloc();
+        bridge.bind_mut().ui_zoom = EditorInterface::singleton().get_editor_scale();
+
+        // Bridge is freed along with the panel.
+        owner.add_child(&bridge.clone());
+
+        let gd_owner = owner.clone();
+        bridge.bind().tap(|x| {
+            x.sync_root_region(Some(owner.clone()));
+            x.register_render_callback_last(
+                0,
+                (move |ctx: &egui::Context| {
+                    if !gd_owner.is_visible_in_tree() {
+                        return;
+                    }
+
+                    egui::CentralPanel::default().show(ctx, |ui| draw(ui));
+                })
+                .bind(owner.clone()),
+            )
+            .detach();
+        });
+
+        self.bridge = Some(bridge);
+    }
+
+    /// Hide the surface along with the panel; e.g. inactive dock tab, or collapsed
+    /// bottom panel.
+    fn on_notification(&mut self, owner: Gd<Control>, what: ControlNotification) {
+        if what != ControlNotification::VISIBILITY_CHANGED {
+            return;
+        }
+
+        if let Some(bridge) = self.bridge.as_mut().filter(|x| x.is_instance_valid()) {
+            bridge.set_visible(owner.is_visible_in_tree());
+        }
+    }
+}
+
+thread_local! {
+    /// Panels installed to the editor; removed along with the plugin, or before the
+    /// library unloads.
+    static INSTALLED_PANELS: RefCell<Vec<InstalledPanel>> = const { RefCell::new(Vec::new()) };
+}
+
+struct InstalledPanel {
+    plugin: InstanceId,
+    panel: InstanceId,
+    on_plugin_exit: Callable,
+    remove: fn(&mut Gd<EditorPlugin>, &Gd<Control>),
+}
+
+impl InstalledPanel {
+    fn uninstall(self) {
+        let plugin = Gd::<EditorPlugin>::try_from_instance_id(self.plugin);
+        let panel = Gd::<Control>::try_from_instance_id(self.panel);
+
+        let Ok(mut plugin) = plugin else {
+            return;
+        };
+
+        if plugin.is_connected("tree_exiting", &self.on_plugin_exit) {
+            plugin.disconnect("tree_exiting", &self.on_plugin_exit);
+        }
+
+        if let Ok(mut panel) = panel {
+            (self.remove)(&mut plugin, &panel);
+            panel.queue_free();
+        }
+    }
+}
+
+/// Remove the panel from the editor and free it, once the plugin exits the tree.
+fn remove_on_plugin_exit(
+    plugin: &mut Gd<EditorPlugin>,
+    panel: Gd<Control>,
+    remove: fn(&mut Gd<EditorPlugin>, &Gd<Control>),
+) {
+    let id_panel = panel.instance_id();
+    let on_plugin_exit = Callable::from_fn("RemovePanel", move |_| {
+        let installed = INSTALLED_PANELS.with_borrow_mut(|x| {
+            let index = x.iter().position(|x| x.panel == id_panel)?;
+            Some(x.swap_remove(index))
+        });
+
+        if let Some(installed) = installed {
+            installed.uninstall();
+        }
+
+        Ok(Variant::nil())
+    });
+
+    plugin.connect("tree_exiting", &on_plugin_exit);
+
+    INSTALLED_PANELS.with_borrow_mut(|x| {
+        x.push(InstalledPanel {
+            plugin: plugin.instance_id(),
+            panel: id_panel,
+            on_plugin_exit,
+            remove,
+        })
+    });
+}
+
+/// Remove every installed panels, as the callbacks can't outlive the library.
+pub(crate) fn teardown_all() {
+    for installed in INSTALLED_PANELS.with_borrow_mut(std::mem::take) {
+        installed.uninstall();
+    }
+}
+
+/* --------------------------------------- Viewport Overlay -------------------------------------- */
+
+/// Draws egui over the editor's 2D and 3D viewports. Each method is meant to be called
+/// from the `EditorPlugin` callback of the same name.
+///
+/// ```ignore
+/// fn forward_canvas_draw_over_viewport(&mut self, overlay: Gd<Control>) {
+///     self.overlay.draw_over_viewport(overlay);
+/// }
+///
+/// fn forward_canvas_gui_input(&mut self, event: Gd<InputEvent>) -> bool {
+///     self.overlay.forward_gui_input(event)
+/// }
+/// ```
+///
+/// The plugin should also return `true` from `handles`, and enable the overlays through
+/// `EditorPlugin::set_input_event_forwarding_always_enabled` and
+/// `set_force_draw_over_forwarding_enabled`.
+pub struct EguiEditorOverlay {
+    bridge: Gd<EguiBridge>,
+    draw: Rc<RefCell<Box<dyn FnMut(&egui::Context)>>>,
+
+    /// Overlay controls that a viewport is attached to.
+    overlays: Vec<(Gd<Control>, ViewportId)>,
+
+    /// Overlay that the mouse was on last time; receives keyboard input.
+    hovered: Option<ViewportId>,
+}
+
+impl EguiEditorOverlay {
+    pub fn new(draw: impl FnMut(&egui::Context) + 'static) -> Self {
+        let edt = EditorInterface::singleton();
+        let mut bridge = EguiBridge::new_alloc();
+        bridge.bind_mut().ui_zoom = edt.get_editor_scale();
+
+        let mut base = edt.get_base_control().expect("editor is not ready!");
+        base.add_child(&bridge.clone());
+
+        Self {
+            bridge,
+            draw: Rc::new(RefCell::new(Box::new(draw))),
+            overlays: Vec::new(),
+            hovered: None,
+        }
+    }
+
+    /// Call from `forward_canvas_draw_over_viewport` or `forward_3d_draw_over_viewport`.
+    pub fn draw_over_viewport(&mut self, overlay: Gd<Control>) {
+        let bridge = self.bridge.bind();
+
+        // 3D viewports are recreated when the editor layout changes.
+        self.overlays.retain(|(x, id)| {
+            x.is_instance_valid() || {
+                bridge.viewport_detach(*id);
+                false
+            }
+        });
+
+        if self.overlays.iter().any(|(x, _)| *x == overlay) {
+            return;
+        }
+
+        let id = ViewportId::from_hash_of(("EguiEditorOverlay", overlay.instance_id()));
+        let draw = self.draw.clone();
+
+        bridge.viewport_attach_to_overlay(id, overlay.clone());
+        bridge.viewport_spawn(id, ViewportBuilder::default(), move |ctx| {
+            draw.borrow_mut()(ctx);
+        });
+
+        self.overlays.push((overlay, id));
+    }
+
+    /// Call from `forward_canvas_gui_input` or `forward_3d_gui_input`. Returns true if
+    /// egui consumed the input; otherwise the editor should handle it as usual.
+    pub fn forward_gui_input(&mut self, event: Gd<classes::InputEvent>) -> bool {
+        if event.clone().try_cast::<classes::InputEventMouse>().is_ok() {
+            // Mouse input goes to the overlay under the cursor.
+            self.hovered = self
+                .overlays
+                .iter()
+                .find(|(x, _)| {
+                    x.is_instance_valid()
+                        && x.is_visible_in_tree()
+                        && Rect2::new(Vector2::ZERO, x.get_size())
+                            .contains_point(x.get_local_mouse_position())
+                })
+                .map(|(_, id)| *id);
+        }
+
+        let Some(id) = self.hovered else {
+            return false;
+        };
+
+        self.bridge.bind().viewport_forward_input(id, event)
+    }
+}
+
+impl Drop for EguiEditorOverlay {
+    fn drop(&mut self) {
+        if self.bridge.is_instance_valid() {
+            self.bridge.queue_free();
+        }
+    }
+}
@@ -9,13 +9,29 @@ macro_rules! symbol_string {
 }
 
 pub mod context;
+pub mod editor;
 mod surface;
 pub mod widgets;
 
 pub extern crate egui;
 
-pub use context::EguiBridge;
+pub use context::{EguiBridge, EguiHandle, FrameStats, PausePolicy};
 pub use egui::{ViewportBuilder, ViewportId};
+pub use widgets::PanelGroup;
+
+/// Tear down every egui resource which refers the code of this library; bridges,
+/// their windows and canvas items, callbacks connected to engine objects, and the
+/// global immediate renderer. Call this from `ExtensionLibrary::on_level_deinit`, so
+/// that the library can be hot-reloaded while egui viewports are open.
+///
+/// Bridges rebuild themselves on the next frame after reload.
+pub fn on_level_deinit(level: godot::init::InitLevel) {
+    // Every level is fine; it's no-op once torn down.
+    let _ = level;
+
+    editor::teardown_all();
+    context::teardown_all();
+}
 
 pub mod helpers {
     use godot::{classes::WeakRef, global, prelude::*};
@@ -113,6 +129,15 @@ pub mod helpers {
         global::weakref(&gd.to_variant()).try_to().unwrap()
     }
 
+    /// Resolve paths without resource scheme under `user://`.
+    pub fn resolve_user_path(path: String) -> String {
+        if path.contains("://") {
+            path
+        } else {
+            format!("user://{path}")
+        }
+    }
+
     pub fn try_upgrade_gd<T: GodotClass>(gd: Gd<WeakRef>) -> Option<Gd<T>> {
         gd.get_ref().try_to().ok()
     }
@@ -13,13 +13,18 @@ use godot::{
 use itertools::multizip;
 use tap::prelude::Tap;
 
-use crate::{helpers::ToCounterpart, DragAndDropVariant};
+use crate::{helpers::ToCounterpart, widgets::InputCapture, DragAndDropVariant};
 
 /* ----------------------------------------- Texture Lib ---------------------------------------- */
 
 #[derive(Default)]
 pub struct TextureLibrary {
     textures: HashMap<egui::TextureId, TextureDescriptor>,
+
+    /// Font atlases for each pixels-per-point, keyed by its bit pattern. egui rasterizes
+    /// fonts separately for each scale, but uploads all of them as
+    /// [`egui::TextureId::default`].
+    font_atlases: HashMap<u32, TextureDescriptor>,
 }
 
 struct TextureDescriptor {
@@ -29,6 +34,25 @@ struct TextureDescriptor {
 
 impl TextureLibrary {
     pub fn update_texture(&mut self, id: egui::TextureId, src: egui::epaint::ImageDelta) {
+        Self::apply_delta(&mut self.textures, id, src);
+    }
+
+    /// Update the font atlas rasterized for given pixels-per-point.
+    pub fn update_font_atlas(&mut self, pixels_per_point: f32, src: egui::epaint::ImageDelta) {
+        Self::apply_delta(&mut self.font_atlases, pixels_per_point.to_bits(), src);
+    }
+
+    /// Drop font atlases of scales that no viewport uses anymore.
+    pub fn retain_font_atlases(&mut self, mut in_use: impl FnMut(f32) -> bool) {
+        self.font_atlases
+            .retain(|ppp, _| in_use(f32::from_bits(*ppp)));
+    }
+
+    fn apply_delta<K: std::hash::Hash + Eq>(
+        textures: &mut HashMap<K, TextureDescriptor>,
+        id: K,
+        src: egui::epaint::ImageDelta,
+    ) {
         // Retrieve image from delivered data
         let src_image = {
             let mut payload = PackedByteArray::new();
@@ -74,7 +98,10 @@ impl TextureLibrary {
         };
 
         if let Some(pos) = src.pos {
-            let tex = self.textures.get_mut(&id).unwrap();
+            let Some(tex) = textures.get_mut(&id) else {
+                godot_error!("Partial update on missing texture!");
+                return;
+            };
 
             let src_size = src_image.get_size();
 
@@ -83,6 +110,9 @@ impl TextureLibrary {
 
             tex.gd_src_img
                 .blit_rect(&src_image, Rect2i::new(Vector2i::ZERO, src_size), dst_pos);
+
+            // Upload the patched image; otherwise new glyphs never reach the GPU.
+            tex.gd_tex.update(&tex.gd_src_img);
         } else {
             let Some(gd_tex) = classes::ImageTexture::create_from_image(&src_image) else {
                 godot_error!("Failed to create texture from image!");
@@ -95,7 +125,7 @@ impl TextureLibrary {
             };
 
             // Replace or insert new texture.
-            self.textures.insert(id, tex);
+            textures.insert(id, tex);
         }
     }
 
@@ -112,15 +142,40 @@ impl TextureLibrary {
     pub fn _clear(&mut self) {
         // RefCounted object doesn't need to be freed manually.
         self.textures.clear();
+        self.font_atlases.clear();
     }
 
-    fn get(&self, id: &egui::TextureId) -> Option<Gd<ImageTexture>> {
-        self.textures.get(id).map(|x| x.gd_tex.clone())
+    fn get(&self, id: &egui::TextureId, pixels_per_point: f32) -> Option<Gd<ImageTexture>> {
+        let font_atlas = (*id == egui::TextureId::default())
+            .then(|| self.font_atlases.get(&pixels_per_point.to_bits()))
+            .flatten();
+
+        font_atlas
+            .or_else(|| self.textures.get(id))
+            .map(|x| x.gd_tex.clone())
     }
 }
 
 /* ------------------------------------------ Viewport ------------------------------------------ */
 
+/// Describes how a painter receives input from the engine.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InputMode {
+    /// Receives input through `gui_input`. Used when the painter exclusively owns its
+    /// window, therefore doesn't need to care about other GUI nodes.
+    #[default]
+    Gui,
+
+    /// Intercepts every input from `_input`, before it reaches out to any GUI node, and
+    /// consumes only what egui wants. Used when the painter shares its window with other
+    /// nodes. (e.g. root viewport, guest viewports)
+    Intercept,
+
+    /// Doesn't receive any input from the engine; the owner forwards input explicitly.
+    /// Mouse positions are local to the parent node. (e.g. editor viewport overlays)
+    Forwarded,
+}
+
 /// Represents a spawned viewport
 #[derive(GodotClass)]
 #[class(base=Control, tool, init, hidden, rename=INTERNAL__GodotEguiViewportBridge)]
@@ -141,6 +196,19 @@ pub(crate) struct EguiViewportBridge {
 
     /// Cached ui scale
     ui_scale_cache: f32,
+
+    /// How this painter receives input.
+    input_mode: InputMode,
+
+    /// Disabled painter ignores any input; e.g. frozen by pause.
+    #[init(val = true)]
+    input_enabled: bool,
+
+    /// While capturing, input is handed over to the capture instead of egui.
+    input_capture: InputCapture,
+
+    /// Space drawn by egui, which should let input through, in points.
+    passthrough_rect: Option<egui::Rect>,
 }
 
 #[godot_api]
@@ -207,6 +275,11 @@ impl IControl for EguiViewportBridge {
     }
 
     fn input(&mut self, event: Gd<classes::InputEvent>) {
+        if !self.base().is_visible_in_tree() {
+            // Hidden surface shouldn't steal any input.
+            return;
+        }
+
         let mut may_drop_payload = false;
 
         if self.try_consume_input(event) {
@@ -229,7 +302,7 @@ impl IControl for EguiViewportBridge {
     }
 
     fn gui_input(&mut self, event: Gd<classes::InputEvent>) {
-        if self.viewport_id == Some(ViewportId::ROOT) {
+        if self.input_mode != InputMode::Gui {
             // See `input` method.
             return;
         }
@@ -259,6 +332,56 @@ impl EguiViewportBridge {
         }
     }
 
+    /// Set how this painter receives input from the engine.
+    pub fn set_input_mode(&mut self, mode: InputMode) {
+        self.input_mode = mode;
+
+        let (filter, process_input) = match mode {
+            InputMode::Gui => (MouseFilter::PASS, false),
+            InputMode::Intercept => (MouseFilter::IGNORE, true),
+            InputMode::Forwarded => (MouseFilter::IGNORE, false),
+        };
+
+        self.base_mut().tap_mut(|b| {
+            b.set_mouse_filter(filter);
+            b.set_process_input(process_input);
+        });
+    }
+
+    /// Disabled painter doesn't consume any input, letting it through to other nodes.
+    pub fn set_input_enabled(&mut self, enabled: bool) {
+        if self.input_enabled && !enabled {
+            // Don't leave hover states behind.
+            self.on_event(egui::Event::PointerGone);
+        }
+
+        self.input_enabled = enabled;
+    }
+
+    /// Share the capture slot of the bridge; see [`crate::widgets::InputRebinder`].
+    pub fn set_input_capture(&mut self, capture: InputCapture) {
+        self.input_capture = capture;
+    }
+
+    /// Number of canvas items which hold the drawn primitives.
+    pub fn canvas_item_count(&self) -> usize {
+        self.canvas_items.len()
+    }
+
+    /// Set the space that pointer input should fall through to the game, unless egui is
+    /// using the pointer or any window is over there; e.g. empty part of the menu bar.
+    pub fn set_passthrough_rect(&mut self, rect: Option<egui::Rect>) {
+        self.passthrough_rect = rect;
+    }
+
+    fn is_passthrough(&self, ctx: &egui::Context, pos: egui::Pos2) -> bool {
+        self.passthrough_rect.is_some_and(|x| x.contains(pos))
+            && !ctx.is_using_pointer()
+            && ctx
+                .layer_id_at(pos)
+                .map_or(true, |x| x.order == egui::Order::Background)
+    }
+
     pub fn initiate(
         &mut self,
         ctx: egui::Context,
@@ -275,23 +398,35 @@ impl EguiViewportBridge {
     /// NOTE: This was separated from virtual `input` method, to make `tool` input
     /// handling available.
     pub fn try_consume_input(&mut self, event: Gd<classes::InputEvent>) -> bool {
+        // Capture started before the input was frozen (e.g. pause menu) should still finish.
+        if self.input_capture.offer(&event) {
+            return true;
+        }
+
+        if !self.input_enabled {
+            return false;
+        }
+
         let Some(ctx) = &self.context else {
             return false;
         };
 
         let ui_scale = self.ui_scale_cache;
-        let pos_offset = self.base().get_global_position().to_counterpart();
+        let pos_offset = match self.input_mode {
+            InputMode::Forwarded => self.base().get_position(),
+            _ => self.base().get_global_position(),
+        }
+        .to_counterpart();
         let calc_mouse_pos =
             |ev: &InputEventMouse| (ev.get_position().to_alternative() - pos_offset) / ui_scale;
 
         let event = match event.try_cast::<InputEventMouseMotion>() {
             Err(event) => event,
             Ok(event) => {
-                self.on_event(egui::Event::PointerMoved(calc_mouse_pos(
-                    event.upcast_ref(),
-                )));
+                let pos = calc_mouse_pos(event.upcast_ref());
+                self.on_event(egui::Event::PointerMoved(pos));
 
-                return ctx.is_pointer_over_area();
+                return ctx.is_pointer_over_area() && !self.is_passthrough(ctx, pos);
             }
         };
 
@@ -389,7 +524,7 @@ impl EguiViewportBridge {
                     }
                 };
 
-                return if ctx.wants_pointer_input() {
+                return if ctx.wants_pointer_input() && !self.is_passthrough(ctx, pos) {
                     // We grab focus only with clicks
                     self.base_mut().grab_focus();
                     true
@@ -500,7 +635,7 @@ impl EguiViewportBridge {
                 continue;
             };
 
-            let Some(texture) = textures.get(&mesh.texture_id) else {
+            let Some(texture) = textures.get(&mesh.texture_id, scale) else {
                 godot_warn!("Missing Texture: {:?}", mesh.texture_id);
                 return;
             };
@@ -0,0 +1,717 @@
+//! Widget related APIs. Detached due to verbosity.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    mem::take,
+    time::{Duration, Instant},
+};
+
+use derive_setters::Setters;
+use godot::prelude::godot_warn;
+
+use crate::context::{CheckExpired, WidgetRetain};
+
+mod console;
+mod dock;
+mod input_rebinder;
+mod object_inspector;
+mod performance;
+mod scene_tree;
+mod variant_edit;
+
+pub(crate) use console::ConsoleCommands;
+pub use console::{Console, ConsoleArgs, LogSeverity};
+pub(crate) use dock::DockContext;
+pub(crate) use input_rebinder::InputCapture;
+pub use input_rebinder::{apply_input_overrides, InputRebinder};
+pub use object_inspector::ObjectInspector;
+pub use performance::PerformanceMonitor;
+pub use scene_tree::SceneTreeInspector;
+pub use variant_edit::VariantEdit;
+
+/* ------------------------------------- Widget Creation ------------------------------------ */
+
+#[derive(Default)]
+pub(crate) struct SpawnedWidgetContext {
+    /// List of widgets
+    items: RefCell<BTreeMap<(PanelGroup, i32), PanelItem>>,
+
+    /// List of widgets, which is spawned this frame's rendering phase.
+    items_new: RefCell<Vec<NewWidgetItem>>,
+
+    /// Root of main menu bar.
+    menu_root: RefCell<MenuNode>,
+
+    /// Menu changes requested since the last frame; applied right before rendering.
+    menu_patches: RefCell<Vec<(Vec<String>, MenuPatch)>>,
+
+    /// Insertion counter of menu nodes, which breaks tie of same order.
+    menu_seq: Cell<u64>,
+
+    pub hide_all: Cell<bool>,
+    pub hide_menu: Cell<bool>,
+
+    pub hide_left: Cell<bool>,
+    pub hide_right: Cell<bool>,
+    pub hide_center: Cell<bool>,
+    pub hide_bottom: Cell<bool>,
+
+    /// Dockable layout of debug panels.
+    pub dock: DockContext,
+}
+
+/// Type alias for widget declaration
+type NewWidgetItem = ((PanelGroup, NewWidgetSlot), Box<FnShowWidget>);
+
+/// Callback for showing spawned widget.
+type FnShowWidget = dyn FnMut(&mut egui::Ui) -> WidgetRetain + 'static;
+
+enum NewWidgetSlot {
+    Specified(i32),
+    Append,
+    Prepend,
+}
+
+/// Widget declaration & context
+struct PanelItem {
+    draw: Box<FnShowWidget>,
+}
+
+/// Callback for menu item actions.
+type FnMenuAction = dyn FnMut() -> WidgetRetain + 'static;
+
+#[derive(Default)]
+struct MenuNode {
+    name: String,
+    options: MenuItemOptions,
+
+    /// Options were set explicitly by `MenuPatch::Options`. Such node is kept while it's
+    /// empty, so that the options can be set ahead of inserting its items.
+    options_set: bool,
+
+    seq: u64,
+    entry: Option<MenuEntry>,
+    children: Vec<MenuNode>,
+}
+
+enum MenuEntry {
+    /// Custom widget drawn in place of the item.
+    Widget(Box<FnShowWidget>),
+
+    /// Button labeled with the item name, which can also be triggered by shortcut.
+    Action(Box<FnMenuAction>),
+}
+
+enum MenuPatch {
+    Insert(MenuItemOptions, MenuEntry),
+    Options(MenuItemOptions),
+    Remove,
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                          PUBLIC TYPES                                          */
+/* ---------------------------------------------------------------------------------------------- */
+
+/* ------------------------------------------ Menu Bar ------------------------------------------ */
+
+/// Options of main menu bar item.
+///
+/// ```no_run
+/// # use gdext_egui::{egui, widgets::MenuItemOptions};
+/// let options = MenuItemOptions::default()
+///     .order(10)
+///     .separator(true)
+///     .shortcut(egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::S));
+/// ```
+#[derive(Setters, Default, Debug, Clone)]
+#[non_exhaustive]
+pub struct MenuItemOptions {
+    /// Items with lower order come first. Items of same order are placed in insertion
+    /// order.
+    pub order: i32,
+
+    /// Draw a separator before this item.
+    pub separator: bool,
+
+    /// Shortcut which triggers the item, even when the menu is closed or the bar is
+    /// hidden. Only effective for actions.
+    #[setters(strip_option)]
+    pub shortcut: Option<egui::KeyboardShortcut>,
+}
+
+/* ------------------------------------- Panel Grouping ------------------------------------- */
+
+/// There are several predefined panels that can be used as a root of the viewport.
+///
+/// These are lazily created if there's any widget that you have added any widget on that
+/// panel group.
+///
+/// ## Layout
+///
+/// ```text
+///         ┌──────────────────────────────────────┐
+///         │add_menu                              │
+///         ├─────────┬─────────────────┬──────────┤
+///         │         │                 │          │
+///         │ Left    │ Central         │ Right    │
+///         │         │                 │          │
+///         ├─────────┴─────────┬───────┴──────────┤
+///         │                   │                  │
+///         │ BottomLeft        │ BottomRight      │
+///         │                   │                  │
+///         └───────────────────┴──────────────────┘
+/// ```
+///
+/// Any space which is not covered by panel or widget passes input through to the game.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum PanelGroup {
+    #[default]
+    Left,
+    Right,
+    Central,
+    BottomRight,
+    BottomLeft,
+}
+
+impl PanelGroup {
+    pub fn range(&self) -> std::ops::RangeInclusive<(Self, i32)> {
+        (*self, i32::MIN)..=(*self, i32::MAX)
+    }
+}
+
+/* ----------------------------------------- Decorators ----------------------------------------- */
+
+/// Base trait for all panel item callbacks.
+pub trait FnPanelDraw<R>: FnMut(&mut egui::Ui) -> R + 'static
+where
+    R: Into<WidgetRetain>,
+{
+}
+
+impl<T, R> FnPanelDraw<R> for T
+where
+    T: FnMut(&mut egui::Ui) -> R + 'static,
+    R: Into<WidgetRetain> + 'static,
+{
+}
+
+/* ------------------------------------------ Extension ----------------------------------------- */
+
+/// Various utilities to extend the panel item callback. Counterpart of
+/// [`crate::context::FnEguiDrawExt`].
+pub trait FnPanelDrawExt<L: Into<WidgetRetain>>: Sized + FnPanelDraw<L> {
+    /// Set the expiration time of the widget. If the widget is not disposed after the given
+    /// system time, it'll be disposed automatically.
+    fn expires_at(mut self, expiration: Instant) -> impl FnPanelDrawExt<WidgetRetain> {
+        move |ui: &mut egui::Ui| {
+            if Instant::now() > expiration {
+                WidgetRetain::Dispose
+            } else {
+                self(ui).into()
+            }
+        }
+    }
+
+    /// Bind the lifetime of the widget to given sentinel. Once it's expired, the widget
+    /// will be disposed automatically.
+    fn bind<C: CheckExpired>(mut self, owner: impl Into<C>) -> impl FnPanelDrawExt<WidgetRetain> {
+        let expired = owner.into();
+        move |ui: &mut egui::Ui| {
+            if expired.expired() {
+                WidgetRetain::Dispose
+            } else {
+                self(ui).into()
+            }
+        }
+    }
+
+    /// Trigger the widget only once. After the first call, the widget will be disposed.
+    fn once(mut self) -> impl FnPanelDrawExt<WidgetRetain> {
+        move |ui: &mut egui::Ui| {
+            // Only the first call will be executed.
+            let _ = self(ui).into();
+            WidgetRetain::Dispose
+        }
+    }
+
+    /// Set the lifespan of the widget. If the widget is not disposed after the given
+    /// time, it'll be disposed automatically.
+    ///
+    /// # Warning
+    ///
+    /// The time is not game delta time, but the system time: Which means, even if you
+    /// stopped the game, the widget will be disposed after the given 'real' time.
+    fn lifespan(self, duration: Duration) -> impl FnPanelDrawExt<WidgetRetain> {
+        self.expires_at(Instant::now() + duration)
+    }
+}
+
+impl<T, L> FnPanelDrawExt<L> for T
+where
+    T: FnMut(&mut egui::Ui) -> L + 'static,
+    L: Into<WidgetRetain> + 'static,
+{
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                              APIS                                              */
+/* ---------------------------------------------------------------------------------------------- */
+
+impl SpawnedWidgetContext {
+    /// See [`crate::EguiBridge::menu_item_insert`].
+    pub fn menu_item_insert<L>(
+        &self,
+        path: Vec<String>,
+        options: MenuItemOptions,
+        mut widget: impl FnPanelDraw<L>,
+    ) where
+        L: Into<WidgetRetain>,
+    {
+        let show = Box::new(move |ui: &mut _| widget(ui).into());
+        self.push_menu_patch(path, MenuPatch::Insert(options, MenuEntry::Widget(show)));
+    }
+
+    /// See [`crate::EguiBridge::menu_action_insert`].
+    pub fn menu_action_insert<L>(
+        &self,
+        path: Vec<String>,
+        options: MenuItemOptions,
+        mut action: impl FnMut() -> L + 'static,
+    ) where
+        L: Into<WidgetRetain>,
+    {
+        let action = Box::new(move || action().into());
+        self.push_menu_patch(path, MenuPatch::Insert(options, MenuEntry::Action(action)));
+    }
+
+    /// See [`crate::EguiBridge::menu_set_options`].
+    pub fn menu_set_options(&self, path: Vec<String>, options: MenuItemOptions) {
+        self.push_menu_patch(path, MenuPatch::Options(options));
+    }
+
+    /// See [`crate::EguiBridge::menu_item_remove`].
+    pub fn menu_item_remove(&self, path: Vec<String>) {
+        self.push_menu_patch(path, MenuPatch::Remove);
+    }
+
+    fn push_menu_patch(&self, path: Vec<String>, patch: MenuPatch) {
+        if path.is_empty() {
+            godot_warn!("Menu item path must not be empty");
+            return;
+        }
+
+        self.menu_patches.borrow_mut().push((path, patch));
+    }
+
+    /// See [`crate::EguiBridge::panel_item_insert`].
+    pub fn panel_item_insert<L>(&self, panel: PanelGroup, slot: i32, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        assert!((i32::MIN >> 1..=i32::MAX >> 1).contains(&slot));
+        self.impl_push_panel_item(panel, NewWidgetSlot::Specified(slot), widget);
+    }
+
+    /// See [`crate::EguiBridge::panel_item_push_back`].
+    pub fn panel_item_push_back<L>(&self, panel: PanelGroup, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.impl_push_panel_item(panel, NewWidgetSlot::Append, widget);
+    }
+
+    /// See [`crate::EguiBridge::panel_item_push_front`].
+    pub fn panel_item_push_front<L>(&self, panel: PanelGroup, widget: impl FnPanelDraw<L>)
+    where
+        L: Into<WidgetRetain>,
+    {
+        self.impl_push_panel_item(panel, NewWidgetSlot::Prepend, widget);
+    }
+
+    /// Hidden state flag of given panel group. Both bottom groups share the same flag.
+    pub fn hide_flag(&self, panel: PanelGroup) -> &Cell<bool> {
+        match panel {
+            PanelGroup::Left => &self.hide_left,
+            PanelGroup::Right => &self.hide_right,
+            PanelGroup::Central => &self.hide_center,
+            PanelGroup::BottomLeft | PanelGroup::BottomRight => &self.hide_bottom,
+        }
+    }
+
+    fn impl_push_panel_item<L>(
+        &self,
+        panel: PanelGroup,
+        slot: NewWidgetSlot,
+        mut widget: impl FnPanelDraw<L>,
+    ) where
+        L: Into<WidgetRetain>,
+    {
+        let show = Box::new(move |ui: &mut _| widget(ui).into());
+        self.items_new.borrow_mut().push(((panel, slot), show));
+    }
+}
+
+/* ------------------------------------------ Internals ----------------------------------------- */
+
+impl MenuNode {
+    /// Find the node of given path, creating missing nodes on the way.
+    fn node_mut(&mut self, path: &[String], seq: &Cell<u64>) -> &mut MenuNode {
+        let Some((name, rest)) = path.split_first() else {
+            return self;
+        };
+
+        let index = match self.children.iter().position(|x| x.name == *name) {
+            Some(index) => index,
+            None => {
+                self.children.push(MenuNode {
+                    name: name.clone(),
+                    seq: seq.replace(seq.get() + 1),
+                    ..Default::default()
+                });
+
+                self.children.len() - 1
+            }
+        };
+
+        self.children[index].node_mut(rest, seq)
+    }
+
+    fn remove(&mut self, path: &[String]) {
+        match path {
+            [] => (),
+            [name] => self.children.retain(|x| x.name != *name),
+            [name, rest @ ..] => {
+                if let Some(child) = self.children.iter_mut().find(|x| x.name == *name) {
+                    child.remove(rest);
+                }
+            }
+        }
+    }
+
+    /// Nothing to draw in this node, including its descendants.
+    fn is_empty(&self) -> bool {
+        self.entry.is_none() && self.children.iter().all(|x| x.is_empty())
+    }
+
+    /// Node can be removed from the tree; descendants should be purged first.
+    fn is_disposable(&self) -> bool {
+        !self.options_set && self.entry.is_none() && self.children.is_empty()
+    }
+
+    fn consume_shortcuts(&mut self, ctx: &egui::Context) {
+        for child in &mut self.children {
+            child.consume_shortcuts(ctx);
+        }
+
+        self.children.retain(|x| !x.is_disposable());
+
+        let Some(shortcut) = self.options.shortcut else {
+            return;
+        };
+
+        if let Some(MenuEntry::Action(action)) = &mut self.entry {
+            if ctx.input_mut(|x| x.consume_shortcut(&shortcut)) && action().disposed() {
+                self.entry = None;
+            }
+        }
+    }
+
+    fn show_children(&mut self, ui: &mut egui::Ui) {
+        self.children.sort_by_key(|x| (x.options.order, x.seq));
+        self.children.retain_mut(|child| {
+            if child.is_empty() {
+                return !child.is_disposable();
+            }
+
+            if child.options.separator {
+                ui.separator();
+            }
+
+            child.show(ui);
+            !child.is_disposable()
+        });
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        if self.children.iter().any(|x| !x.is_empty()) {
+            // Widget of submenu node is drawn on top of the submenu.
+            let name = self.name.clone();
+            ui.menu_button(name, |ui| {
+                if let Some(MenuEntry::Widget(draw)) = &mut self.entry {
+                    if draw(ui).disposed() {
+                        self.entry = None;
+                    }
+                }
+
+                self.show_children(ui);
+            });
+
+            return;
+        }
+
+        match &mut self.entry {
+            Some(MenuEntry::Widget(draw)) => {
+                if draw(ui).disposed() {
+                    self.entry = None;
+                }
+            }
+            Some(MenuEntry::Action(action)) => {
+                let mut button = egui::Button::new(&self.name);
+
+                if let Some(shortcut) = &self.options.shortcut {
+                    button = button.shortcut_text(ui.ctx().format_shortcut(shortcut));
+                }
+
+                if ui.add(button).clicked() {
+                    ui.close_menu();
+
+                    if action().disposed() {
+                        self.entry = None;
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+}
+
+impl SpawnedWidgetContext {
+    /// Lay out every panel of the root viewport. This should be called at the very start
+    /// of the frame, before any user widget, so that the rest of the UI respects the
+    /// space taken by the panels.
+    ///
+    /// Returns the space which is occupied by egui, however, should let input through to
+    /// the game; e.g. empty part of the menu bar.
+    pub(crate) fn start_frame_handle_widgets(&self, ctx: &egui::Context) -> Option<egui::Rect> {
+        self.apply_menu_patches();
+
+        // Shortcuts are available even if the bar is hidden.
+        self.menu_root.borrow_mut().consume_shortcuts(ctx);
+
+        if self.hide_all.get() {
+            return None;
+        }
+
+        // NOTE: Panels are laid out directly on the context, never inside a
+        // `CentralPanel`, which would claim the space left by panels.
+        //
+        // Menu bar is the top-most panel; it should be laid out before any other panel.
+        let passthrough = self.render_main_menu(ctx);
+
+        // Render widgets
+        self.render_widget_items(ctx);
+
+        // Dock is a floating window; it's laid out over the space left by panels.
+        self.dock.render(ctx);
+
+        passthrough
+    }
+
+    fn apply_menu_patches(&self) {
+        let patches = take(&mut *self.menu_patches.borrow_mut());
+        let mut root = self.menu_root.borrow_mut();
+
+        for (path, patch) in patches {
+            match patch {
+                MenuPatch::Insert(options, entry) => {
+                    let node = root.node_mut(&path, &self.menu_seq);
+                    node.options = options;
+                    node.entry = Some(entry);
+                }
+                MenuPatch::Options(options) => {
+                    let node = root.node_mut(&path, &self.menu_seq);
+                    node.options = options;
+                    node.options_set = true;
+                }
+                MenuPatch::Remove => root.remove(&path),
+            }
+        }
+    }
+
+    fn render_main_menu(&self, ctx: &egui::Context) -> Option<egui::Rect> {
+        let mut root = self.menu_root.borrow_mut();
+
+        if root.is_empty() || self.hide_menu.get() {
+            return None;
+        }
+
+        let panel = egui::TopBottomPanel::top("%%EguiBridge%%MainMenu").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                root.show_children(ui);
+                ui.min_rect()
+            })
+            .inner
+        });
+
+        // The panel spans the whole width, while the items take only the left side.
+        let (bar, used) = (panel.response.rect, panel.inner);
+        Some(egui::Rect::from_min_max(
+            egui::pos2(used.max.x, bar.min.y),
+            bar.max,
+        ))
+    }
+
+    fn render_widget_items(&self, ctx: &egui::Context) {
+        // Apply widget patches right before rendering. New items are taken out first,
+        // since the widgets may spawn another widget during rendering.
+        let items_new = std::mem::take(&mut *self.items_new.borrow_mut());
+        let widgets = &mut *self.items.borrow_mut();
+        let w = self;
+
+        const APPEND_SLOT_LOWER: i32 = i32::MAX >> 1;
+        const PREPEND_SLOT_UPPER: i32 = i32::MIN >> 1;
+
+        /* --------------------------------- New Slot Allocation -------------------------------- */
+
+        for ((group, slot), draw) in items_new {
+            let slot_index = match slot {
+                NewWidgetSlot::Specified(idx) => idx,
+                NewWidgetSlot::Append => {
+                    let idx = widgets
+                        .range(group.range())
+                        .last() // For btree range, it's fast.
+                        .map(|((_, idx), ..)| *idx + 1)
+                        .unwrap_or_default()
+                        .max(APPEND_SLOT_LOWER);
+
+                    idx
+                }
+                NewWidgetSlot::Prepend => {
+                    let idx = widgets
+                        .range(group.range())
+                        .next()
+                        .map(|((_, idx), ..)| *idx - 1)
+                        .unwrap_or_default()
+                        .min(PREPEND_SLOT_UPPER);
+
+                    idx
+                }
+            };
+
+            widgets.insert((group, slot_index), PanelItem { draw });
+        }
+
+        // Based on layout; draw widgets
+        let enums = [
+            PanelGroup::Left,
+            PanelGroup::Right,
+            PanelGroup::Central,
+            PanelGroup::BottomLeft,
+            PanelGroup::BottomRight,
+        ];
+
+        let [has_left, has_right, has_center, has_bottom_left, has_bottom_right] =
+            enums.map(|x| widgets.range_mut(x.range()).any(|_| true));
+        let has_top = has_left || has_right || has_center;
+        let has_bottom = has_bottom_left || has_bottom_right;
+
+        let mut disposed = Vec::new();
+
+        macro_rules! draw_group {
+            (#[plain], $ui:expr, $panel:expr) => {{
+                for (index, item) in widgets.range_mut($panel.range()) {
+                    let retain = (item.draw)($ui);
+
+                    if retain == WidgetRetain::Dispose {
+                        disposed.push(*index);
+                    }
+                }
+            }};
+
+            ($ui:expr, $panel:expr) => {{
+                egui::ScrollArea::new([true, true])
+                    .id_source(stringify!($panel))
+                    .show($ui, |ui| {
+                        draw_group!(#[plain], ui, $panel);
+                    });
+            }};
+        }
+
+        // Draw transparent frame to fill the empty space.
+        let transparent = egui::Frame::default().fill(egui::Color32::from_black_alpha(0));
+        let opaque = egui::Frame::default().fill(egui::Color32::from_black_alpha(71));
+
+        // Draw bottom side of panels first; let top side expand as much as possible.
+        egui::TopBottomPanel::bottom("%%EguiBridge%%PanelBottom")
+            .frame(opaque)
+            .resizable(true)
+            .show_animated(ctx, has_bottom && !w.hide_bottom.get(), |ui| {
+                match (has_bottom_left, has_bottom_right) {
+                    (true, true) => {
+                        ui.columns(2, |col| {
+                            draw_group!(&mut col[0], PanelGroup::BottomLeft);
+                            draw_group!(&mut col[1], PanelGroup::BottomRight);
+                        });
+                    }
+                    (true, false) => draw_group!(ui, PanelGroup::BottomLeft),
+                    (false, true) => draw_group!(ui, PanelGroup::BottomRight),
+                    (false, false) => unreachable!(),
+                }
+            });
+
+        let width = ctx.available_rect().width();
+
+        if has_top {
+            egui::SidePanel::left("%%EguiBridge%%PanelLeft")
+                .resizable(true)
+                .frame(opaque)
+                .max_width(width / 3.)
+                .show_animated(ctx, has_left && !w.hide_left.get(), |ui| {
+                    draw_group!(ui, PanelGroup::Left)
+                });
+
+            egui::SidePanel::right("%%EguiBridge%%PanelRight")
+                .resizable(true)
+                .frame(opaque)
+                .max_width(width / 3.)
+                .show_animated(ctx, has_right && !w.hide_right.get(), |ui| {
+                    draw_group!(ui, PanelGroup::Right)
+                });
+
+            if has_center && !w.hide_center.get() {
+                // To allow clicks on the empty space, here we create window with
+                // transparent frame.
+                egui::Window::new("%%EguiBridge%%PanelCenter")
+                    .title_bar(false)
+                    .constrain_to(ctx.available_rect())
+                    .frame(transparent)
+                    .auto_sized()
+                    .anchor(egui::Align2::LEFT_TOP, [0., 0.])
+                    .show(ctx, |ui| {
+                        draw_group!(#[plain], ui, PanelGroup::Central);
+                    });
+            }
+        }
+
+        if has_top || has_bottom {
+            // Popup visibility control display
+            egui::Window::new("Visibility")
+                .id("%%EguiBridge%%Visibility".into())
+                .title_bar(false)
+                .auto_sized()
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        [
+                            (&w.hide_center, "Center"),
+                            (&w.hide_left, "Left"),
+                            (&w.hide_right, "Right"),
+                            (&w.hide_bottom, "Bottom"),
+                        ]
+                        .into_iter()
+                        .for_each(|(hide, label)| {
+                            let mut hidden = !hide.get();
+                            ui.checkbox(&mut hidden, label);
+                            hide.set(!hidden);
+                        });
+                    })
+                });
+        }
+
+        // Gc removed entries.
+        for index in disposed {
+            assert!(widgets.remove(&index).is_some());
+        }
+    }
+}
@@ -0,0 +1,776 @@
+//! In-game console, which shows engine log output and runs registered commands.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::Rc,
+    str::FromStr,
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+use godot::{
+    classes::{self, file_access::ModeFlags, FileAccess},
+    prelude::*,
+};
+
+/// How often the log file is checked for new output.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Maximum number of candidates shown by autocomplete.
+const MAX_COMPLETIONS: usize = 8;
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                             COMMANDS                                           */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Handler of a console command. Returned text is printed to the console.
+type FnConsoleCommand = dyn FnMut(&ConsoleArgs) -> Result<String, String> + 'static;
+
+/// Registry of console commands, shared between the bridge and its consoles.
+#[derive(Clone, Default)]
+pub(crate) struct ConsoleCommands(Rc<RefCell<Vec<ConsoleCommand>>>);
+
+struct ConsoleCommand {
+    name: String,
+    description: String,
+    handler: Rc<RefCell<Box<FnConsoleCommand>>>,
+}
+
+impl ConsoleCommands {
+    /// Silently replaces the existing command of the same name.
+    pub fn register(&self, name: String, description: String, handler: Box<FnConsoleCommand>) {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            godot_warn!("Invalid console command name: '{name}'");
+            return;
+        }
+
+        let mut commands = self.0.borrow_mut();
+        commands.retain(|x| x.name != name);
+        commands.push(ConsoleCommand {
+            name,
+            description,
+            handler: Rc::new(RefCell::new(handler)),
+        });
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.0.borrow_mut().retain(|x| x.name != name);
+    }
+
+    /// Register a command which calls given callable with parsed arguments; see
+    /// [`ConsoleArgs::to_variants`].
+    pub fn register_callable(&self, name: String, description: String, callable: Callable) {
+        let handler = move |args: &ConsoleArgs| {
+            if !callable.is_valid() {
+                return Err("Command target is no longer valid".to_owned());
+            }
+
+            let result = callable.callv(&args.to_variants());
+
+            Ok(match result.is_nil() {
+                true => String::new(),
+                false => result.stringify().to_string(),
+            })
+        };
+
+        self.register(name, description, Box::new(handler));
+    }
+
+    fn names_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        self.0
+            .borrow()
+            .iter()
+            .filter(|x| x.name.starts_with(prefix))
+            .map(|x| (x.name.clone(), x.description.clone()))
+            .collect()
+    }
+
+    fn find(&self, name: &str) -> Option<Rc<RefCell<Box<FnConsoleCommand>>>> {
+        self.0
+            .borrow()
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| x.handler.clone())
+    }
+}
+
+/// Arguments of a console command. Arguments are separated by whitespaces; quote them
+/// with `"` or `'` to include whitespaces.
+#[derive(Debug, Clone)]
+pub struct ConsoleArgs {
+    args: Vec<String>,
+}
+
+impl ConsoleArgs {
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Raw arguments, without the command name.
+    pub fn raw(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Parse an argument of given index.
+    pub fn get<T>(&self, index: usize) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let arg = self
+            .args
+            .get(index)
+            .ok_or_else(|| format!("Missing argument #{index}"))?;
+
+        arg.parse()
+            .map_err(|e| format!("Invalid argument #{index} '{arg}': {e}"))
+    }
+
+    /// Parse an argument of given index, if given.
+    pub fn get_or<T>(&self, index: usize, default: T) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        match index < self.args.len() {
+            true => self.get(index),
+            false => Ok(default),
+        }
+    }
+
+    /// Convert arguments into variants; integers, floats and booleans are parsed, and
+    /// the rest are passed as strings.
+    pub fn to_variants(&self) -> VariantArray {
+        self.args
+            .iter()
+            .map(|x| {
+                if let Ok(x) = x.parse::<i64>() {
+                    x.to_variant()
+                } else if let Ok(x) = x.parse::<f64>() {
+                    x.to_variant()
+                } else if let Ok(x) = x.parse::<bool>() {
+                    x.to_variant()
+                } else {
+                    GString::from(x.as_str()).to_variant()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Split a command line into tokens, respecting quotes and backslash escapes.
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut token = None::<String>;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (_, '\\') => {
+                let escaped = chars.next().ok_or("Unexpected end of line after '\\'")?;
+                token.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), ch) => token.get_or_insert_with(String::new).push(ch),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                token.get_or_insert_with(String::new);
+            }
+            (None, ch) if ch.is_whitespace() => tokens.extend(token.take()),
+            (None, ch) => token.get_or_insert_with(String::new).push(ch),
+        }
+    }
+
+    if quote.is_some() {
+        return Err("Unterminated quote".to_owned());
+    }
+
+    tokens.extend(token);
+    Ok(tokens)
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                              WIDGET                                            */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Severity of console entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSeverity {
+    Info,
+    Warning,
+    Error,
+
+    /// Echo of commands entered.
+    Command,
+}
+
+impl LogSeverity {
+    const ALL: [Self; 4] = [Self::Info, Self::Warning, Self::Error, Self::Command];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Classify a line of engine log output.
+    fn of_log_line(line: &str) -> Self {
+        let line = line.trim_start();
+
+        if [
+            "ERROR:",
+            "SCRIPT ERROR:",
+            "USER ERROR:",
+            "USER SCRIPT ERROR:",
+        ]
+        .iter()
+        .any(|x| line.starts_with(x))
+        {
+            Self::Error
+        } else if ["WARNING:", "USER WARNING:", "SCRIPT WARNING:"]
+            .iter()
+            .any(|x| line.starts_with(x))
+        {
+            Self::Warning
+        } else {
+            Self::Info
+        }
+    }
+}
+
+struct Entry {
+    severity: LogSeverity,
+    text: String,
+}
+
+/// Console widget, which tails the engine log file and runs registered commands.
+///
+/// # Limitations
+///
+/// Engine output can't be hooked directly; Godot 4.3 has no `Logger` API, and gdext has no
+/// print hook. Instead, output (including `godot_print!` and errors) is read from the log
+/// file of `debug/file_logging/log_path` project setting, therefore:
+///
+/// - Nothing is shown unless `debug/file_logging/enable_file_logging` is enabled; the
+///   console tells so, and a warning is printed once.
+/// - Lines show up only after Godot flushes the file; on errors, or on every print if
+///   `application/run/flush_stdout_on_print` is enabled.
+/// - Only the output written after the console first reads the file is shown; the log of
+///   the session so far is not replayed.
+///
+/// Output of your own code can be shown immediately with [`Self::push`].
+///
+/// Consoles created by [`crate::EguiBridge::console`] share the commands registered to
+/// the bridge, including the ones registered from GDScript.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::{*, widgets::ConsoleArgs};
+/// # let bridge = EguiBridge::new_alloc();
+/// let bridge = bridge.bind();
+///
+/// bridge.console_command_register("add", "Adds two integers", |args: &ConsoleArgs| {
+///     let (a, b) = (args.get::<i64>(0)?, args.get::<i64>(1)?);
+///     Ok(format!("{}", a + b))
+/// });
+///
+/// bridge
+///     .register_render_callback_last(0, bridge.console().into_window("Console"))
+///     .detach();
+/// ```
+pub struct Console {
+    commands: ConsoleCommands,
+    entries: VecDeque<Entry>,
+    max_entries: usize,
+
+    log: Option<LogTail>,
+
+    /// Shown severities, indexed by [`LogSeverity::index`].
+    shown: [bool; 4],
+    search: String,
+
+    input: String,
+    history: Vec<String>,
+    history_cursor: Option<usize>,
+
+    /// Told that there's no log to tail.
+    log_missing_told: bool,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Console {
+    /// Create a console with its own commands. See also [`crate::EguiBridge::console`].
+    pub fn new() -> Self {
+        Self::with_commands(ConsoleCommands::default())
+    }
+
+    pub(crate) fn with_commands(commands: ConsoleCommands) -> Self {
+        Self {
+            commands,
+            entries: VecDeque::new(),
+            max_entries: 2000,
+            log: LogTail::from_project_settings(),
+            shown: [true; 4],
+            search: String::new(),
+            input: String::new(),
+            history: Vec::new(),
+            history_cursor: None,
+            log_missing_told: false,
+        }
+    }
+
+    /// Number of entries kept; older ones are discarded.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Tail given log file instead of the one of project settings. Empty string disables
+    /// tailing.
+    pub fn log_file(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+
+        // Disabled on purpose; nothing to tell.
+        self.log_missing_told = path.is_empty();
+        self.log = (!path.is_empty()).then(|| LogTail::new(path));
+        self
+    }
+
+    /// Register a command to this console. Commands are shared with the bridge, if the
+    /// console is created by [`crate::EguiBridge::console`].
+    pub fn register_command(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        handler: impl FnMut(&ConsoleArgs) -> Result<String, String> + 'static,
+    ) {
+        self.commands
+            .register(name.into(), description.into(), Box::new(handler));
+    }
+
+    /// Print a line to the console.
+    pub fn push(&mut self, severity: LogSeverity, text: impl Into<String>) {
+        self.entries.push_back(Entry {
+            severity,
+            text: text.into(),
+        });
+
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Run a command line, as if it's entered by user.
+    pub fn execute(&mut self, line: &str) {
+        self.push(LogSeverity::Command, format!("> {line}"));
+
+        let mut tokens = match tokenize(line) {
+            Ok(x) if x.is_empty() => return,
+            Ok(x) => x,
+            Err(e) => return self.push(LogSeverity::Error, e),
+        };
+
+        let name = tokens.remove(0);
+        let args = ConsoleArgs { args: tokens };
+
+        // Built-in commands
+        match name.as_str() {
+            "help" => {
+                for (name, description) in self.commands.names_with_prefix("") {
+                    self.push(LogSeverity::Info, format!("{name} - {description}"));
+                }
+
+                self.push(LogSeverity::Info, "clear - Clears the console");
+                return;
+            }
+            "clear" => return self.clear(),
+            _ => (),
+        }
+
+        let Some(handler) = self.commands.find(&name) else {
+            return self.push(LogSeverity::Error, format!("Unknown command: '{name}'"));
+        };
+
+        // Handler may register or unregister commands; registry is not borrowed here.
+        let Ok(mut handler) = handler.try_borrow_mut() else {
+            return self.push(LogSeverity::Error, format!("'{name}' is already running"));
+        };
+
+        match (*handler)(&args) {
+            Ok(output) if output.is_empty() => (),
+            Ok(output) => self.push(LogSeverity::Info, output),
+            Err(e) => self.push(LogSeverity::Error, e),
+        }
+    }
+
+    /// Wrap this console into a window, which can be registered with
+    /// [`crate::EguiBridge::register_render_callback_last`].
+    pub fn into_window(
+        mut self,
+        title: impl Into<egui::WidgetText>,
+    ) -> impl FnMut(&egui::Context) + 'static {
+        let title = title.into();
+
+        move |ctx| {
+            egui::Window::new(title.clone())
+                .default_size([560., 320.])
+                .show(ctx, |ui| self.show(ui));
+        }
+    }
+
+    /// Draw the console.
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.poll_log(ui.ctx());
+
+        ui.horizontal(|ui| {
+            for severity in LogSeverity::ALL {
+                ui.toggle_value(&mut self.shown[severity.index()], format!("{severity:?}"));
+            }
+
+            ui.separator();
+            ui.label("Search");
+            ui.text_edit_singleline(&mut self.search);
+        });
+
+        ui.separator();
+
+        // Leave space for the command line.
+        let reserved = ui.spacing().interact_size.y * 2. + ui.spacing().item_spacing.y * 2.;
+        let height = (ui.available_height() - reserved).max(0.);
+
+        self.show_entries(ui, height);
+
+        ui.separator();
+        self.show_command_line(ui);
+    }
+
+    fn show_entries(&self, ui: &mut egui::Ui, height: f32) {
+        let search = self.search.to_lowercase();
+        let shown = self
+            .entries
+            .iter()
+            .filter(|x| self.shown[x.severity.index()])
+            .filter(|x| search.is_empty() || x.text.to_lowercase().contains(&search))
+            .collect::<Vec<_>>();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+
+        egui::ScrollArea::vertical()
+            .max_height(height)
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, shown.len(), |ui, range| {
+                for entry in &shown[range] {
+                    let color = match entry.severity {
+                        LogSeverity::Info => ui.visuals().text_color(),
+                        LogSeverity::Warning => ui.visuals().warn_fg_color,
+                        LogSeverity::Error => ui.visuals().error_fg_color,
+                        LogSeverity::Command => ui.visuals().weak_text_color(),
+                    };
+
+                    ui.label(egui::RichText::new(&entry.text).monospace().color(color));
+                }
+            });
+    }
+
+    fn show_command_line(&mut self, ui: &mut egui::Ui) {
+        let id = ui.id().with("%%Console%%Input");
+        let focused = ui.memory(|x| x.has_focus(id));
+
+        // Keys are taken before the text edit sees them.
+        if focused {
+            let (tab, up, down) = ui.input_mut(|x| {
+                (
+                    x.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+                    x.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                    x.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                )
+            });
+
+            if tab {
+                self.complete();
+            }
+
+            if up || down {
+                self.browse_history(up);
+            }
+        }
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.input)
+                .id(id)
+                .font(egui::TextStyle::Monospace)
+                .hint_text("Enter command; `help` lists commands")
+                .lock_focus(true)
+                .desired_width(f32::INFINITY),
+        );
+
+        if response.lost_focus() && ui.input(|x| x.key_pressed(egui::Key::Enter)) {
+            let line = std::mem::take(&mut self.input);
+
+            if !line.trim().is_empty() {
+                self.history.retain(|x| *x != line);
+                self.history.push(line.clone());
+                self.history_cursor = None;
+                self.execute(&line);
+            }
+
+            response.request_focus();
+        }
+
+        // Candidates are listed while typing command name.
+        if response.has_focus() && !self.input.is_empty() && !self.input.contains(' ') {
+            let candidates = self.commands.names_with_prefix(&self.input);
+
+            for (name, description) in candidates.iter().take(MAX_COMPLETIONS) {
+                ui.horizontal(|ui| {
+                    ui.monospace(name);
+                    ui.weak(description);
+                });
+            }
+        }
+    }
+
+    /// Complete command name to the longest common prefix of candidates.
+    fn complete(&mut self) {
+        if self.input.contains(' ') {
+            return;
+        }
+
+        let candidates = self.commands.names_with_prefix(&self.input);
+        let Some((first, _)) = candidates.first() else {
+            return;
+        };
+
+        let common = candidates.iter().fold(first.as_str(), |common, (name, _)| {
+            let len = common
+                .char_indices()
+                .zip(name.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(name.len()), |((index, _), _)| index);
+
+            &common[..len]
+        });
+
+        self.input = common.to_owned();
+
+        if candidates.len() == 1 {
+            self.input.push(' ');
+        }
+    }
+
+    fn browse_history(&mut self, backward: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        let last = self.history.len() - 1;
+        self.history_cursor = match (self.history_cursor, backward) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(x), true) => Some(x.saturating_sub(1)),
+            (Some(x), false) if x < last => Some(x + 1),
+            (Some(_), false) => None,
+        };
+
+        self.input = match self.history_cursor {
+            Some(x) => self.history[x].clone(),
+            None => String::new(),
+        };
+    }
+
+    fn tell_log_missing(&mut self) {
+        const MESSAGE: &str = "File logging is disabled; engine output won't be shown. \
+            Enable `debug/file_logging/enable_file_logging` project setting.";
+
+        static WARN_ONCE: Once = Once::new();
+        WARN_ONCE.call_once(|| godot_warn!("Console: {MESSAGE}"));
+
+        self.log_missing_told = true;
+        self.push(LogSeverity::Warning, MESSAGE);
+    }
+
+    fn poll_log(&mut self, ctx: &egui::Context) {
+        let Some(log) = &mut self.log else {
+            if !self.log_missing_told {
+                self.tell_log_missing();
+            }
+
+            return;
+        };
+
+        let lines = log.poll();
+
+        // Keep polling, even in reactive mode.
+        ctx.request_repaint_after(LOG_POLL_INTERVAL);
+
+        // Continuation lines, e.g. `at: ...` of errors, follow their severity.
+        let mut severity = self.entries.back().map(|x| x.severity);
+
+        for line in lines {
+            let continued = line.starts_with(' ') || line.starts_with('\t');
+            let current = match (continued, severity) {
+                (true, Some(x)) if x != LogSeverity::Command => x,
+                _ => LogSeverity::of_log_line(&line),
+            };
+
+            severity = Some(current);
+            self.push(current, line);
+        }
+    }
+}
+
+/* ------------------------------------------ Log Tail ------------------------------------------ */
+
+/// Reads lines appended to a file.
+struct LogTail {
+    path: String,
+
+    /// Kept open between polls; `None` until the file can be opened.
+    file: Option<Gd<FileAccess>>,
+
+    position: u64,
+    partial: Vec<u8>,
+    polled_at: Option<Instant>,
+}
+
+impl LogTail {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            file: None,
+            position: 0,
+            partial: Vec::new(),
+            polled_at: None,
+        }
+    }
+
+    fn from_project_settings() -> Option<Self> {
+        let settings = classes::ProjectSettings::singleton();
+
+        if !settings
+            .get_setting("debug/file_logging/enable_file_logging")
+            .try_to::<bool>()
+            .unwrap_or(false)
+        {
+            return None;
+        }
+
+        let path = settings
+            .get_setting("debug/file_logging/log_path")
+            .try_to::<GString>()
+            .ok()?;
+
+        Some(Self::new(path.to_string()))
+    }
+
+    /// Complete lines appended since the last poll.
+    fn poll(&mut self) -> Vec<String> {
+        if self
+            .polled_at
+            .is_some_and(|x| x.elapsed() < LOG_POLL_INTERVAL)
+        {
+            return Vec::new();
+        }
+
+        self.polled_at = Some(Instant::now());
+
+        let mut file = match &self.file {
+            Some(file) => file.clone(),
+            None => {
+                let Some(file) = FileAccess::open(self.path.as_str(), ModeFlags::READ) else {
+                    return Vec::new();
+                };
+
+                // Start from the end, rather than dumping the whole session at once.
+                self.position = file.get_length();
+                self.file = Some(file.clone());
+                file
+            }
+        };
+
+        let length = file.get_length();
+
+        if length < self.position {
+            // Log file is truncated.
+            self.position = 0;
+            self.partial.clear();
+        }
+
+        if length == self.position {
+            return Vec::new();
+        }
+
+        file.seek(self.position);
+        let bytes = file.get_buffer((length - self.position) as i64);
+        self.position = length;
+
+        self.partial.extend_from_slice(bytes.as_slice());
+
+        let Some(end) = self.partial.iter().rposition(|x| *x == b'\n') else {
+            return Vec::new();
+        };
+
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .map(|x| x.trim_end_matches('\r').to_owned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tokenize;
+
+    fn tokens(line: &str) -> Vec<String> {
+        tokenize(line).unwrap()
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace() {
+        assert_eq!(tokens("  a b\t c  "), ["a", "b", "c"]);
+        assert!(tokens("").is_empty());
+        assert!(tokens("   ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_quotes() {
+        assert_eq!(tokens(r#"say "hello world""#), ["say", "hello world"]);
+        assert_eq!(tokens(r#"'it"s' "it's""#), [r#"it"s"#, "it's"]);
+        assert_eq!(tokens(r#"a"b c"d"#), ["ab cd"]);
+
+        // Empty quotes are still a token.
+        assert_eq!(tokens(r#"a "" ''"#), ["a", "", ""]);
+    }
+
+    #[test]
+    fn tokenize_escapes() {
+        assert_eq!(tokens(r"a\ b c"), ["a b", "c"]);
+        assert_eq!(tokens(r#""a\"b" 'c\'d'"#), [r#"a"b"#, "c'd"]);
+        assert_eq!(tokens(r"\\"), ["\\"]);
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        assert!(tokenize(r#"say "hello"#).is_err());
+        assert!(tokenize("say 'hello").is_err());
+        assert!(tokenize(r"say \").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Dockable tab layout of panels, which can be rearranged by the user.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use egui_dock::{DockArea, DockState, TabViewer};
+use godot::{classes, prelude::*};
+
+use crate::context::WidgetRetain;
+
+use super::FnShowWidget;
+
+#[derive(Default)]
+pub(crate) struct DockContext {
+    /// Registered panels, in registration order.
+    panels: RefCell<Vec<DockPanel>>,
+
+    /// Panels registered during rendering; merged on next frame.
+    panels_new: RefCell<Vec<DockPanel>>,
+
+    /// Panels unregistered since the last frame.
+    panels_removed: RefCell<Vec<String>>,
+
+    /// Layout tree of tabs. Tabs refer panels by ID, and may contain panels which are
+    /// not registered yet; e.g. restored from the saved layout.
+    state: RefCell<Option<DockState<String>>>,
+
+    /// Tabs of unknown panels were removed from the restored layout.
+    pruned: Cell<bool>,
+
+    /// Rebuild the layout on next frame. Shared with the menu action.
+    reset_requested: Rc<Cell<bool>>,
+
+    /// Any panel was ever registered. The layout is not saved until then.
+    used: Cell<bool>,
+
+    pub hidden: Cell<bool>,
+}
+
+struct DockPanel {
+    id: String,
+    title: String,
+    draw: Box<FnShowWidget>,
+}
+
+impl DockContext {
+    /// See [`crate::EguiBridge::dock_panel_register`]. Returns true if this is the first
+    /// registration of the dock.
+    pub fn register(&self, id: String, title: String, draw: Box<FnShowWidget>) -> bool {
+        self.panels_new
+            .borrow_mut()
+            .push(DockPanel { id, title, draw });
+
+        !self.used.replace(true)
+    }
+
+    /// See [`crate::EguiBridge::dock_panel_unregister`].
+    pub fn unregister(&self, id: String) {
+        self.panels_removed.borrow_mut().push(id);
+    }
+
+    /// See [`crate::EguiBridge::dock_reset_layout`].
+    pub fn reset_layout(&self) {
+        self.reset_requested.set(true);
+    }
+
+    /// Flag which resets the layout on next frame, once set.
+    pub fn reset_trigger(&self) -> Rc<Cell<bool>> {
+        self.reset_requested.clone()
+    }
+
+    /// Restore the layout from given file, if exist.
+    pub fn load_layout(&self, path: &str) {
+        if !classes::FileAccess::file_exists(path) {
+            // Nothing saved yet.
+            return;
+        }
+
+        let payload = classes::FileAccess::get_file_as_string(path).to_string();
+        match ron::from_str::<DockState<String>>(&payload) {
+            Ok(state) => {
+                *self.state.borrow_mut() = Some(state);
+                self.pruned.set(false);
+            }
+            Err(e) => godot_warn!("Discarding dock layout from '{path}': {e}"),
+        }
+    }
+
+    /// Save the current layout into given file. Nothing is saved if no panel has ever
+    /// been registered.
+    pub fn save_layout(&self, path: &str) {
+        if !self.used.get() {
+            return;
+        }
+
+        let state = self.state.borrow();
+        let Some(state) = state.as_ref() else {
+            return;
+        };
+
+        let payload = match ron::to_string(state) {
+            Ok(x) => x,
+            Err(e) => {
+                godot_error!("Failed to serialize dock layout: {e}");
+                return;
+            }
+        };
+
+        let Some(mut file) =
+            classes::FileAccess::open(path, classes::file_access::ModeFlags::WRITE)
+        else {
+            let err = classes::FileAccess::get_open_error();
+            godot_error!("Failed to open '{path}' to save dock layout: {err:?}");
+            return;
+        };
+
+        file.store_string(&payload);
+    }
+
+    pub(crate) fn render(&self, ctx: &egui::Context) {
+        self.apply_patches();
+
+        let mut panels = self.panels.borrow_mut();
+        let mut state = self.state.borrow_mut();
+
+        let state = state.get_or_insert_with(|| DockState::new(Vec::new()));
+
+        // Saved layout may refer panels which are not used anymore; they'd stay as
+        // placeholders, and be saved again. Panels are usually registered on startup,
+        // therefore the first render with any panel decides.
+        if !panels.is_empty() && !self.pruned.replace(true) {
+            let unknown = state
+                .iter_all_tabs()
+                .map(|(_, tab)| tab)
+                .filter(|tab| !panels.iter().any(|x| x.id == **tab))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for id in unknown {
+                remove_tab(state, &id);
+            }
+        }
+
+        // Every registered panel should have its tab.
+        for panel in panels.iter() {
+            if state.find_tab(&panel.id).is_none() {
+                state
+                    .main_surface_mut()
+                    .push_to_focused_leaf(panel.id.clone());
+            }
+        }
+
+        if panels.is_empty() || self.hidden.get() {
+            return;
+        }
+
+        let mut viewer = Viewer {
+            panels: &mut panels,
+            disposed: Vec::new(),
+        };
+
+        egui::Window::new("Debug Panels")
+            .id(egui::Id::new("%%EguiBridge%%Dock"))
+            .default_size([480., 320.])
+            .resizable(true)
+            .show(ctx, |ui| {
+                DockArea::new(state)
+                    .id(egui::Id::new("%%EguiBridge%%DockArea"))
+                    .style(egui_dock::Style::from_egui(ui.style()))
+                    .show_close_buttons(false)
+                    .show_inside(ui, &mut viewer);
+            });
+
+        let disposed = viewer.disposed;
+        panels.retain(|x| !disposed.contains(&x.id));
+
+        for id in disposed {
+            remove_tab(state, &id);
+        }
+    }
+
+    fn apply_patches(&self) {
+        let mut panels = self.panels.borrow_mut();
+        let mut state = self.state.borrow_mut();
+
+        for id in self.panels_removed.take() {
+            panels.retain(|x| x.id != id);
+
+            if let Some(state) = state.as_mut() {
+                remove_tab(state, &id);
+            }
+        }
+
+        for panel in self.panels_new.take() {
+            match panels.iter_mut().find(|x| x.id == panel.id) {
+                Some(slot) => *slot = panel,
+                None => panels.push(panel),
+            }
+        }
+
+        if self.reset_requested.take() {
+            let tabs = panels.iter().map(|x| x.id.clone()).collect();
+            *state = Some(DockState::new(tabs));
+        }
+    }
+}
+
+fn remove_tab(state: &mut DockState<String>, id: &String) {
+    if let Some(index) = state.find_tab(id) {
+        state.remove_tab(index);
+    }
+}
+
+struct Viewer<'a> {
+    panels: &'a mut Vec<DockPanel>,
+    disposed: Vec<String>,
+}
+
+impl TabViewer for Viewer<'_> {
+    type Tab = String;
+
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        match self.panels.iter().find(|x| x.id == *tab) {
+            Some(panel) => panel.title.as_str().into(),
+            None => tab.as_str().into(),
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
+        let Some(panel) = self.panels.iter_mut().find(|x| x.id == *tab) else {
+            // Tabs of unknown panels are pruned on the first render; shouldn't happen.
+            ui.weak("This panel is not registered.");
+            return;
+        };
+
+        if (panel.draw)(ui) == WidgetRetain::Dispose {
+            self.disposed.push(tab.clone());
+        }
+    }
+
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        egui::Id::new(("%%EguiBridge%%DockTab", &*tab))
+    }
+
+    fn closeable(&mut self, _tab: &mut Self::Tab) -> bool {
+        false
+    }
+}
@@ -0,0 +1,594 @@
+//! Rebinding of `InputMap` actions, with overrides persisted across sessions.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use godot::{
+    classes::{
+        ConfigFile, FileAccess, InputEvent, InputEventJoypadButton, InputEventJoypadMotion,
+        InputEventKey, InputEventMouseButton, InputEventMouseMotion, InputMap, ProjectSettings,
+        Resource,
+    },
+    global::{self, Key},
+    prelude::*,
+};
+
+use crate::helpers::resolve_user_path;
+
+/// Section of the overrides file, which maps action names to their events.
+const OVERRIDES_SECTION: &str = "input";
+
+/// Capture is abandoned if the widget stops polling it; e.g. its window was closed.
+const CAPTURE_STALE_AFTER: Duration = Duration::from_secs(1);
+
+/// Joypad axes should be pushed further than this to be captured.
+const CAPTURE_AXIS_THRESHOLD: f32 = 0.5;
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                             CAPTURE                                            */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Slot for capturing the next input event, shared between the bridge, its painters and
+/// rebinders. While waiting, painters hand every input over here instead of egui.
+#[derive(Clone, Default)]
+pub(crate) struct InputCapture(Rc<RefCell<CaptureState>>);
+
+#[derive(Default)]
+enum CaptureState {
+    #[default]
+    Idle,
+    Waiting {
+        polled_at: Instant,
+    },
+    Captured(Gd<InputEvent>),
+    Cancelled,
+}
+
+impl InputCapture {
+    fn start(&self) {
+        *self.0.borrow_mut() = CaptureState::Waiting {
+            polled_at: Instant::now(),
+        };
+    }
+
+    fn cancel(&self) {
+        *self.0.borrow_mut() = CaptureState::Idle;
+    }
+
+    /// Take the finished result, or keep waiting.
+    fn poll(&self) -> CaptureState {
+        let mut state = self.0.borrow_mut();
+
+        match &mut *state {
+            CaptureState::Idle => CaptureState::Idle,
+            CaptureState::Waiting { polled_at } => {
+                *polled_at = Instant::now();
+                CaptureState::Waiting {
+                    polled_at: *polled_at,
+                }
+            }
+            CaptureState::Captured(_) | CaptureState::Cancelled => std::mem::take(&mut *state),
+        }
+    }
+
+    /// Offer an input event delivered to a painter. Returns true if the capture swallowed
+    /// the event; nothing but pointer motion should reach egui while capturing.
+    pub fn offer(&self, event: &Gd<InputEvent>) -> bool {
+        let mut state = self.0.borrow_mut();
+
+        let CaptureState::Waiting { polled_at } = &*state else {
+            return false;
+        };
+
+        if polled_at.elapsed() > CAPTURE_STALE_AFTER {
+            *state = CaptureState::Idle;
+            return false;
+        }
+
+        // Lets the rebinder know whether a captured click was aimed at its cancel button.
+        if event.clone().try_cast::<InputEventMouseMotion>().is_ok() {
+            return false;
+        }
+
+        if let Ok(key) = event.clone().try_cast::<InputEventKey>() {
+            if key.get_keycode() == Key::ESCAPE || key.get_physical_keycode() == Key::ESCAPE {
+                *state = CaptureState::Cancelled;
+                return true;
+            }
+        }
+
+        if let Some(event) = capturable_event(event) {
+            *state = CaptureState::Captured(event);
+        }
+
+        true
+    }
+}
+
+/// Copy of given event suitable for binding, or `None` if it shouldn't be bound; e.g.
+/// releases, echoes, motions or slightly tilted axes.
+fn capturable_event(event: &Gd<InputEvent>) -> Option<Gd<InputEvent>> {
+    let accept = if let Ok(x) = event.clone().try_cast::<InputEventKey>() {
+        x.is_pressed() && !x.is_echo()
+    } else if let Ok(x) = event.clone().try_cast::<InputEventMouseButton>() {
+        x.is_pressed()
+    } else if let Ok(x) = event.clone().try_cast::<InputEventJoypadButton>() {
+        x.is_pressed()
+    } else if let Ok(x) = event.clone().try_cast::<InputEventJoypadMotion>() {
+        x.get_axis_value().abs() >= CAPTURE_AXIS_THRESHOLD
+    } else {
+        false
+    };
+
+    if !accept {
+        return None;
+    }
+
+    let mut event = event
+        .clone()
+        .upcast::<Resource>()
+        .duplicate()?
+        .try_cast::<InputEvent>()
+        .ok()?;
+
+    // Bindings are not tied to the device which happened to be used.
+    event.set_device(-1);
+
+    if let Ok(mut x) = event.clone().try_cast::<InputEventJoypadMotion>() {
+        x.set_axis_value(x.get_axis_value().signum());
+    }
+
+    Some(event)
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                            OVERRIDES                                           */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Replace events of actions with the ones saved by [`InputRebinder`]. Relative paths are
+/// resolved under `user://`. Actions which don't exist yet are skipped; call this again
+/// after adding actions at runtime.
+///
+/// [`crate::EguiBridge`] calls this once with its
+/// [`crate::EguiBridge::input_overrides_path`], if set.
+pub fn apply_input_overrides(path: &str) {
+    let path = resolve_user_path(path.into());
+
+    if !FileAccess::file_exists(path.as_str()) {
+        return;
+    }
+
+    let mut config = ConfigFile::new_gd();
+    let err = config.load(path.as_str());
+
+    if err != global::Error::OK {
+        godot_warn!("Failed to load input overrides from '{path}': {err:?}");
+        return;
+    }
+
+    if !config.has_section(OVERRIDES_SECTION) {
+        return;
+    }
+
+    let mut input_map = InputMap::singleton();
+
+    for key in config.get_section_keys(OVERRIDES_SECTION).as_slice() {
+        let action = StringName::from(key);
+
+        if !input_map.has_action(&action) {
+            continue;
+        }
+
+        let Ok(events) = config
+            .get_value(OVERRIDES_SECTION, key)
+            .try_to::<VariantArray>()
+        else {
+            godot_warn!("Discarding input override of '{action}' from '{path}'");
+            continue;
+        };
+
+        input_map.action_erase_events(&action);
+
+        for event in events.iter_shared() {
+            if let Ok(event) = event.try_to::<Gd<InputEvent>>() {
+                input_map.action_add_event(&action, &event);
+            }
+        }
+    }
+}
+
+/// Events of given action as defined in project settings; `None` for actions added at
+/// runtime.
+fn project_events(action: &StringName) -> Option<Vec<Gd<InputEvent>>> {
+    let setting = ProjectSettings::singleton()
+        .get_setting(format!("input/{action}").as_str())
+        .try_to::<Dictionary>()
+        .ok()?;
+
+    let events = setting
+        .get("events")
+        .and_then(|x| x.try_to::<VariantArray>().ok())
+        .map(|x| {
+            x.iter_shared()
+                .filter_map(|x| x.try_to::<Gd<InputEvent>>().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(events)
+}
+
+/// Replace events of given action with the ones from project settings. Returns true if
+/// anything changed; actions added at runtime have nothing to be reset to.
+fn reset_action(action: &StringName) -> bool {
+    let Some(defaults) = project_events(action) else {
+        return false;
+    };
+
+    if same_events(&action_events(action), &defaults) {
+        return false;
+    }
+
+    let mut input_map = InputMap::singleton();
+    input_map.action_erase_events(action);
+
+    for event in &defaults {
+        input_map.action_add_event(action, event);
+    }
+
+    true
+}
+
+fn action_events(action: &StringName) -> Vec<Gd<InputEvent>> {
+    InputMap::singleton()
+        .action_get_events(action)
+        .iter_shared()
+        .collect()
+}
+
+/// Whether both lists bind the same inputs, regardless of order.
+fn same_events(a: &[Gd<InputEvent>], b: &[Gd<InputEvent>]) -> bool {
+    let contains =
+        |list: &[Gd<InputEvent>], event: &Gd<InputEvent>| list.iter().any(|x| x.is_match(event));
+
+    a.len() == b.len() && a.iter().all(|x| contains(b, x)) && b.iter().all(|x| contains(a, x))
+}
+
+/* ---------------------------------------------------------------------------------------------- */
+/*                                             WIDGET                                             */
+/* ---------------------------------------------------------------------------------------------- */
+
+/// Keybinding settings widget, which lists `InputMap` actions with their bindings.
+///
+/// Clicking `+` of an action captures the next key, mouse button or joypad input as a new
+/// binding; egui doesn't receive any input but pointer motion meanwhile. `Escape` or the
+/// `Cancel` button next to the action cancels. Bindings shared by multiple actions are
+/// marked as conflicts.
+///
+/// Every change is applied to `InputMap` immediately, and the bindings which differ from
+/// project settings are saved to the overrides file, if any, which is reapplied on
+/// startup. See [`apply_input_overrides`].
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::*;
+/// # let bridge = EguiBridge::new_alloc();
+/// let bridge = bridge.bind();
+///
+/// bridge
+///     .register_render_callback_last(0, bridge.input_rebinder().into_window("Controls"))
+///     .detach();
+/// ```
+pub struct InputRebinder {
+    capture: InputCapture,
+
+    /// Resolved path of the overrides file; `None` disables persistence.
+    overrides_path: Option<String>,
+
+    /// Show built-in `ui_*` actions.
+    show_builtin: bool,
+
+    filter: String,
+
+    /// Action which is waiting for the captured input.
+    capturing: Option<StringName>,
+
+    /// Area of the cancel button, drawn while capturing. Clicks are captured before egui
+    /// sees them, therefore they're matched against this instead.
+    cancel_rect: Option<egui::Rect>,
+}
+
+impl InputRebinder {
+    pub(crate) fn with_capture(capture: InputCapture, overrides_path: Option<String>) -> Self {
+        Self {
+            capture,
+            overrides_path,
+            show_builtin: false,
+            filter: String::new(),
+            capturing: None,
+            cancel_rect: None,
+        }
+    }
+
+    /// File to save overrides to. Relative paths are resolved under `user://`. Empty
+    /// string disables persistence.
+    pub fn overrides_path(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        self.overrides_path = (!path.is_empty()).then(|| resolve_user_path(path));
+        self
+    }
+
+    /// Whether to list built-in `ui_*` actions, which are hidden by default.
+    pub fn show_builtin(mut self, show: bool) -> Self {
+        self.show_builtin = show;
+        self
+    }
+
+    /// Whether this widget is waiting for an input to bind.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing.is_some()
+    }
+
+    /// Wrap this widget into a window, which can be registered with
+    /// [`crate::EguiBridge::register_render_callback_last`].
+    pub fn into_window(
+        mut self,
+        title: impl Into<egui::WidgetText>,
+    ) -> impl FnMut(&egui::Context) + 'static {
+        let title = title.into();
+
+        move |ctx| {
+            egui::Window::new(title.clone())
+                .default_size([480., 360.])
+                .show(ctx, |ui| self.show(ui));
+        }
+    }
+
+    /// Draw the widget. Returns true if any binding was changed in this frame.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = self.poll_capture(ui.ctx());
+        let mut reset_all = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.text_edit_singleline(&mut self.filter);
+            ui.checkbox(&mut self.show_builtin, "Built-in");
+
+            reset_all = ui
+                .button("Reset All")
+                .on_hover_text("Reset every listed action to project settings")
+                .clicked();
+        });
+
+        if let Some(action) = &self.capturing {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("Press any key, button or axis for '{action}'; Escape cancels."),
+            );
+        }
+
+        ui.separator();
+
+        let filter = self.filter.to_lowercase();
+        let is_listed = |action: &StringName| {
+            filter.is_empty() || action.to_string().to_lowercase().contains(&filter)
+        };
+
+        let actions = InputMap::singleton()
+            .get_actions()
+            .iter_shared()
+            .filter(|x| self.show_builtin || !x.to_string().starts_with("ui_"))
+            .collect::<Vec<_>>();
+
+        if reset_all {
+            for action in actions.iter().filter(|x| is_listed(x)) {
+                changed |= reset_action(action);
+            }
+        }
+
+        let actions = actions
+            .into_iter()
+            .map(|x| {
+                let events = action_events(&x);
+                (x, events)
+            })
+            .collect::<Vec<_>>();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("%%InputRebinder%%Actions")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (action, events) in &actions {
+                            if !is_listed(action) {
+                                continue;
+                            }
+
+                            changed |= self.show_action(ui, action, events, &actions);
+                        }
+                    });
+            });
+
+        if changed {
+            self.save_overrides();
+        }
+
+        changed
+    }
+
+    fn show_action(
+        &mut self,
+        ui: &mut egui::Ui,
+        action: &StringName,
+        events: &[Gd<InputEvent>],
+        actions: &[(StringName, Vec<Gd<InputEvent>>)],
+    ) -> bool {
+        let mut changed = false;
+        let mut input_map = InputMap::singleton();
+
+        ui.label(action.to_string());
+
+        ui.horizontal_wrapped(|ui| {
+            for event in events {
+                let conflicts = actions
+                    .iter()
+                    .filter(|(other, events)| {
+                        other != action && events.iter().any(|x| x.is_match(event))
+                    })
+                    .map(|(other, _)| other.to_string())
+                    .collect::<Vec<_>>();
+
+                let mut text = egui::RichText::new(format!("{} ×", event.as_text()));
+                if !conflicts.is_empty() {
+                    text = text.color(ui.visuals().warn_fg_color);
+                }
+
+                let hover = if conflicts.is_empty() {
+                    "Click to remove this binding".to_owned()
+                } else {
+                    format!(
+                        "Conflicts with: {}\nClick to remove this binding",
+                        conflicts.join(", ")
+                    )
+                };
+
+                let response = ui.small_button(text).on_hover_text(hover);
+
+                if response.clicked() {
+                    input_map.action_erase_event(action, event);
+                    changed = true;
+                }
+            }
+
+            if events.is_empty() {
+                ui.weak("Unbound");
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let capturing = self.capturing.as_ref() == Some(action);
+            let add = ui.add_enabled(
+                self.capturing.is_none() || capturing,
+                egui::Button::new(if capturing { "…" } else { "+" }).small(),
+            );
+
+            if add.on_hover_text("Add a binding").clicked() && !capturing {
+                self.capturing = Some(action.clone());
+                self.cancel_rect = None;
+                self.capture.start();
+            }
+
+            if capturing {
+                let cancel = ui.small_button("Cancel");
+                self.cancel_rect = Some(cancel.rect);
+
+                if cancel.clicked() {
+                    self.capture.cancel();
+                    self.capturing = None;
+                }
+            }
+
+            let resettable = project_events(action).is_some_and(|x| !same_events(events, &x));
+
+            if ui
+                .add_enabled(resettable, egui::Button::new("Reset").small())
+                .clicked()
+            {
+                changed |= reset_action(action);
+            }
+        });
+
+        ui.end_row();
+
+        changed
+    }
+
+    /// Bind the captured input, if any. Returns true if a binding was added.
+    fn poll_capture(&mut self, ctx: &egui::Context) -> bool {
+        let Some(action) = self.capturing.clone() else {
+            return false;
+        };
+
+        match self.capture.poll() {
+            CaptureState::Waiting { .. } => {
+                // Captured input doesn't reach egui, therefore won't trigger repaint.
+                ctx.request_repaint();
+                false
+            }
+            CaptureState::Captured(event) => {
+                self.capturing = None;
+
+                let cancel_rect = self.cancel_rect.take();
+                let on_cancel = ctx
+                    .input(|x| x.pointer.hover_pos())
+                    .is_some_and(|pos| cancel_rect.is_some_and(|x| x.contains(pos)));
+
+                if on_cancel && event.clone().try_cast::<InputEventMouseButton>().is_ok() {
+                    return false;
+                }
+
+                let mut input_map = InputMap::singleton();
+                if !input_map.has_action(&action) {
+                    return false;
+                }
+
+                if action_events(&action).iter().any(|x| x.is_match(&event)) {
+                    return false;
+                }
+
+                input_map.action_add_event(&action, &event);
+                true
+            }
+            CaptureState::Cancelled | CaptureState::Idle => {
+                self.capturing = None;
+                false
+            }
+        }
+    }
+
+    /// Write bindings which differ from project settings to the overrides file.
+    fn save_overrides(&self) {
+        let Some(path) = &self.overrides_path else {
+            return;
+        };
+
+        let mut config = ConfigFile::new_gd();
+
+        for action in InputMap::singleton().get_actions().iter_shared() {
+            let events = action_events(&action);
+            if same_events(&events, &project_events(&action).unwrap_or_default()) {
+                continue;
+            }
+
+            let events = events
+                .iter()
+                .map(|x| x.to_variant())
+                .collect::<VariantArray>();
+
+            config.set_value(
+                OVERRIDES_SECTION,
+                action.to_string().as_str(),
+                &events.to_variant(),
+            );
+        }
+
+        let err = config.save(path.as_str());
+        if err != global::Error::OK {
+            godot_warn!("Failed to save input overrides to '{path}': {err:?}");
+        }
+    }
+}
+
+impl Drop for InputRebinder {
+    fn drop(&mut self) {
+        // Don't leave the painters swallowing input.
+        if self.capturing.is_some() {
+            self.capture.cancel();
+        }
+    }
+}
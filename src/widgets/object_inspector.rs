@@ -0,0 +1,477 @@
+//! Reflection based inspector of Godot objects.
+
+use godot::{
+    classes::Resource,
+    global::{PropertyHint, PropertyUsageFlags},
+    obj::{EngineBitfield, EngineEnum},
+    prelude::*,
+};
+
+use super::VariantEdit;
+
+/// Nested resources deeper than this are not expanded; they may refer each other.
+const MAX_DEPTH: usize = 8;
+
+/// Widget which edits properties of any object, reading them from
+/// `Object::get_property_list()`. This doesn't depend on the editor, therefore works on
+/// release builds.
+///
+/// Each property is drawn by its type and hint; ranges as sliders, enums as combo boxes,
+/// flags as checkboxes, colors as pickers and nested resources as collapsibles. Edits
+/// are written back through `Object::set()`.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::{*, widgets::ObjectInspector};
+/// # let bridge = EguiBridge::new_alloc();
+/// # let node = Node::new_alloc();
+/// let inspector = ObjectInspector::new(node.upcast());
+///
+/// bridge
+///     .bind()
+///     .register_render_callback_last(0, inspector.into_window("Inspector"))
+///     .detach();
+/// ```
+#[derive(Default)]
+pub struct ObjectInspector {
+    object: Option<Gd<Object>>,
+    filter: String,
+}
+
+impl ObjectInspector {
+    pub fn new(object: Gd<Object>) -> Self {
+        Self {
+            object: Some(object),
+            filter: String::new(),
+        }
+    }
+
+    /// Currently inspected object, if it's still alive.
+    pub fn object(&self) -> Option<Gd<Object>> {
+        self.object.clone().filter(|x| x.is_instance_valid())
+    }
+
+    pub fn set_object(&mut self, object: Option<Gd<Object>>) {
+        self.object = object;
+    }
+
+    /// Wrap this inspector into a window, which can be registered with
+    /// [`crate::EguiBridge::register_render_callback_last`].
+    pub fn into_window(
+        mut self,
+        title: impl Into<egui::WidgetText>,
+    ) -> impl FnMut(&egui::Context) + 'static {
+        let title = title.into();
+
+        move |ctx| {
+            egui::Window::new(title.clone())
+                .default_size([320., 480.])
+                .show(ctx, |ui| {
+                    self.show(ui);
+                });
+        }
+    }
+
+    /// Draw the inspector. Returns true if any property was changed in this frame.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let Some(mut object) = self.object() else {
+            ui.weak("Nothing to inspect.");
+            return false;
+        };
+
+        ui.horizontal(|ui| {
+            ui.strong(object.get_class().to_string());
+
+            if let Ok(node) = object.clone().try_cast::<Node>() {
+                ui.weak(node.get_name().to_string());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.text_edit_singleline(&mut self.filter);
+        });
+
+        ui.separator();
+
+        let filter = self.filter.to_lowercase();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| show_properties(ui, &mut object, &filter, 0))
+            .inner
+    }
+}
+
+/// Draw every editable property of given object. Returns true if any value was changed.
+fn show_properties(ui: &mut egui::Ui, object: &mut Gd<Object>, filter: &str, depth: usize) -> bool {
+    let mut changed = false;
+
+    ui.push_id(object.instance_id(), |ui| {
+        egui::Grid::new("%%ObjectInspector%%Properties")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for property in object.get_property_list().iter_shared() {
+                    changed |= show_property(ui, object, &property, filter, depth);
+                }
+            });
+    });
+
+    changed
+}
+
+fn show_property(
+    ui: &mut egui::Ui,
+    object: &mut Gd<Object>,
+    property: &Dictionary,
+    filter: &str,
+    depth: usize,
+) -> bool {
+    let name = property.get_or_nil("name").to::<StringName>();
+    let usage = property.get_or_nil("usage").to::<i64>() as u64;
+
+    let is = |flag: PropertyUsageFlags| usage & flag.ord() != 0;
+
+    if is(PropertyUsageFlags::CATEGORY) || is(PropertyUsageFlags::GROUP) {
+        // Group headers are meaningless while filtering.
+        if filter.is_empty() {
+            ui.strong(name.to_string());
+            ui.end_row();
+        }
+
+        return false;
+    }
+
+    if !is(PropertyUsageFlags::EDITOR) || is(PropertyUsageFlags::SUBGROUP) {
+        return false;
+    }
+
+    let label = name.to_string();
+    if !filter.is_empty() && !label.to_lowercase().contains(filter) {
+        return false;
+    }
+
+    // Newer engines may report hints which the bound API doesn't know.
+    let hint = PropertyHint::try_from_ord(property.get_or_nil("hint").to::<i64>() as i32)
+        .unwrap_or(PropertyHint::NONE);
+    let hint_string = property
+        .get_or_nil("hint_string")
+        .to::<GString>()
+        .to_string();
+
+    ui.label(&label);
+
+    let mut value = object.get(&name);
+    let changed = ui
+        .add_enabled_ui(!is(PropertyUsageFlags::READ_ONLY), |ui| {
+            edit_value(ui, &mut value, hint, &hint_string, depth)
+        })
+        .inner;
+
+    ui.end_row();
+
+    if changed {
+        object.set(&name, &value);
+    }
+
+    changed
+}
+
+/// Draw the editor of a value by its type and hint. Returns true if the value was
+/// changed; resources are edited in place, therefore never reported as changed.
+///
+/// Only the hinted values and resources are handled here; everything else is drawn by
+/// [`VariantEdit`].
+fn edit_value(
+    ui: &mut egui::Ui,
+    value: &mut Variant,
+    hint: PropertyHint,
+    hint_string: &str,
+    depth: usize,
+) -> bool {
+    match (value.get_type(), hint) {
+        (VariantType::INT, PropertyHint::ENUM | PropertyHint::FLAGS | PropertyHint::RANGE) => {
+            let mut x = value.to::<i64>();
+            let changed = match hint {
+                PropertyHint::ENUM => edit_enum(ui, &mut x, hint_string),
+                PropertyHint::FLAGS => edit_flags(ui, &mut x, hint_string),
+                _ => {
+                    let range = parse_range(hint_string);
+                    ui.add(
+                        egui::Slider::new(&mut x, range.min as i64..=range.max as i64)
+                            .step_by(range.step)
+                            .clamp_to_range(range.is_bounded()),
+                    )
+                    .changed()
+                }
+            };
+
+            *value = x.to_variant();
+            changed
+        }
+
+        (VariantType::FLOAT, PropertyHint::RANGE) => {
+            let mut x = value.to::<f64>();
+            let range = parse_range(hint_string);
+            let changed = ui
+                .add(
+                    egui::Slider::new(&mut x, range.min..=range.max)
+                        .step_by(range.step)
+                        .clamp_to_range(range.is_bounded()),
+                )
+                .changed();
+
+            *value = x.to_variant();
+            changed
+        }
+
+        (VariantType::STRING, PropertyHint::ENUM) => {
+            let mut x = value.to::<GString>().to_string();
+            let items = hint_string.split(',').map(|x| (x.to_owned(), x.to_owned()));
+            let changed = combo_box(ui, &mut x, items);
+
+            *value = GString::from(x).to_variant();
+            changed
+        }
+
+        (VariantType::STRING, PropertyHint::MULTILINE_TEXT) => {
+            let mut x = value.to::<GString>().to_string();
+            let changed = ui.text_edit_multiline(&mut x).changed();
+
+            *value = GString::from(x).to_variant();
+            changed
+        }
+
+        (VariantType::COLOR, PropertyHint::COLOR_NO_ALPHA) => {
+            let color = value.to::<Color>();
+            let mut rgb = [color.r, color.g, color.b];
+            let changed = ui.color_edit_button_rgb(&mut rgb).changed();
+
+            *value = Color::from_rgba(rgb[0], rgb[1], rgb[2], color.a).to_variant();
+            changed
+        }
+
+        (VariantType::OBJECT, _) if value.try_to::<Gd<Resource>>().is_ok() => {
+            let resource = value.to::<Gd<Resource>>();
+            let title = match resource.get_path().to_string() {
+                path if path.is_empty() => resource.get_class().to_string(),
+                path => format!("{} ({path})", resource.get_class()),
+            };
+
+            if depth >= MAX_DEPTH {
+                ui.weak(title);
+                return false;
+            }
+
+            egui::CollapsingHeader::new(title)
+                .id_source(resource.instance_id())
+                .show(ui, |ui| {
+                    show_properties(ui, &mut resource.upcast(), "", depth + 1);
+                });
+
+            false
+        }
+
+        _ => ui.add(VariantEdit::new(value)).changed(),
+    }
+}
+
+fn edit_enum(ui: &mut egui::Ui, value: &mut i64, hint_string: &str) -> bool {
+    // Implicit values continue from the previous item; e.g. "A,B:5,C" is 0, 5, 6.
+    let items = parse_hint_items(hint_string, |index, previous| {
+        previous.map_or(index, |x| x + 1)
+    });
+
+    combo_box(ui, value, items)
+}
+
+fn edit_flags(ui: &mut egui::Ui, value: &mut i64, hint_string: &str) -> bool {
+    let mut changed = false;
+
+    ui.vertical(|ui| {
+        for (name, bit) in parse_hint_items(hint_string, |index, _| 1 << index) {
+            let mut set = *value & bit != 0;
+
+            if ui.checkbox(&mut set, name).changed() {
+                *value = if set { *value | bit } else { *value & !bit };
+                changed = true;
+            }
+        }
+    });
+
+    changed
+}
+
+fn combo_box<T: PartialEq + Clone>(
+    ui: &mut egui::Ui,
+    value: &mut T,
+    items: impl IntoIterator<Item = (String, T)>,
+) -> bool {
+    let items = items.into_iter().collect::<Vec<_>>();
+    let selected = items
+        .iter()
+        .find(|(_, x)| x == value)
+        .map(|(name, _)| name.clone())
+        .unwrap_or_default();
+
+    let mut changed = false;
+
+    egui::ComboBox::from_id_source(ui.next_auto_id())
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for (name, item) in items {
+                changed |= ui.selectable_value(value, item, name).changed();
+            }
+        });
+
+    changed
+}
+
+/// Parse items of enum or flags hint; `"A,B,C"` or `"A:5,B:10"`. Items without explicit
+/// value take `implicit(index, previous)`.
+fn parse_hint_items(
+    hint_string: &str,
+    implicit: impl Fn(i64, Option<i64>) -> i64,
+) -> Vec<(String, i64)> {
+    let mut previous = None;
+
+    hint_string
+        .split(',')
+        .filter(|x| !x.is_empty())
+        .enumerate()
+        .map(|(index, item)| {
+            let (name, value) = match item.rsplit_once(':') {
+                Some((name, value)) => match value.trim().parse() {
+                    Ok(value) => (name, value),
+                    Err(_) => (item, implicit(index as i64, previous)),
+                },
+                None => (item, implicit(index as i64, previous)),
+            };
+
+            previous = Some(value);
+            (name.trim().to_owned(), value)
+        })
+        .collect()
+}
+
+/// Range hint of a numeric property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RangeHint {
+    min: f64,
+    max: f64,
+    step: f64,
+    or_greater: bool,
+    or_less: bool,
+}
+
+impl RangeHint {
+    /// Whether values outside `min..=max` are not allowed.
+    fn is_bounded(&self) -> bool {
+        !(self.or_greater || self.or_less)
+    }
+}
+
+/// Parse `"min,max[,step][,or_greater][,or_less][,...]"` of range hint.
+fn parse_range(hint_string: &str) -> RangeHint {
+    let mut items = hint_string.split(',').map(str::trim).peekable();
+    let mut number = |default: f64| {
+        items
+            .next_if(|x| x.parse::<f64>().is_ok())
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(default)
+    };
+
+    let min = number(0.);
+    let max = number(100.);
+    let step = number(0.);
+    let flags = items.collect::<Vec<_>>();
+
+    RangeHint {
+        min,
+        max,
+        step,
+        or_greater: flags.contains(&"or_greater"),
+        or_less: flags.contains(&"or_less"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_hint_items, parse_range, RangeHint};
+
+    fn enum_items(hint_string: &str) -> Vec<(String, i64)> {
+        parse_hint_items(hint_string, |index, previous| {
+            previous.map_or(index, |x| x + 1)
+        })
+    }
+
+    fn items(list: &[(&str, i64)]) -> Vec<(String, i64)> {
+        list.iter().map(|(x, v)| (x.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn hint_items_implicit_values() {
+        assert_eq!(enum_items("A,B,C"), items(&[("A", 0), ("B", 1), ("C", 2)]));
+        assert_eq!(
+            enum_items("A,B:5,C"),
+            items(&[("A", 0), ("B", 5), ("C", 6)])
+        );
+        assert_eq!(enum_items("A:-1,B"), items(&[("A", -1), ("B", 0)]));
+
+        let flags = parse_hint_items("A,B,C:8", |index, _| 1 << index);
+        assert_eq!(flags, items(&[("A", 1), ("B", 2), ("C", 8)]));
+    }
+
+    #[test]
+    fn hint_items_names() {
+        assert_eq!(enum_items(" A : 3 , B "), items(&[("A", 3), ("B", 4)]));
+        assert_eq!(enum_items("A,,B"), items(&[("A", 0), ("B", 1)]));
+        assert!(enum_items("").is_empty());
+
+        // Only a numeric suffix is a value.
+        assert_eq!(
+            enum_items("Ratio 1:x,Time 10:30"),
+            items(&[("Ratio 1:x", 0), ("Time 10", 30)])
+        );
+    }
+
+    fn range(min: f64, max: f64, step: f64, or_greater: bool, or_less: bool) -> RangeHint {
+        RangeHint {
+            min,
+            max,
+            step,
+            or_greater,
+            or_less,
+        }
+    }
+
+    #[test]
+    fn range_numbers() {
+        assert_eq!(parse_range(""), range(0., 100., 0., false, false));
+        assert_eq!(parse_range("-5,5"), range(-5., 5., 0., false, false));
+        assert_eq!(
+            parse_range(" 0 , 1 , 0.01 "),
+            range(0., 1., 0.01, false, false)
+        );
+    }
+
+    #[test]
+    fn range_suffixes() {
+        assert_eq!(
+            parse_range("0,10,1,or_greater"),
+            range(0., 10., 1., true, false)
+        );
+        assert_eq!(
+            parse_range("0,10,or_less,or_greater"),
+            range(0., 10., 0., true, true)
+        );
+        assert_eq!(
+            parse_range("0,360,0.1,radians_as_degrees,suffix:°"),
+            range(0., 360., 0.1, false, false)
+        );
+
+        assert!(parse_range("0,10,hide_slider").is_bounded());
+        assert!(!parse_range("0,10,or_less").is_bounded());
+    }
+}
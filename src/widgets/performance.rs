@@ -0,0 +1,277 @@
+//! Performance monitor overlay.
+
+use std::collections::VecDeque;
+
+use egui_plot::{Line, Plot, PlotPoints};
+use godot::{
+    classes::{self, performance::Monitor},
+    prelude::*,
+};
+
+use crate::{context::WakeActions, FrameStats};
+
+/// Overlay which plots rolling history of engine monitors of `Performance` singleton,
+/// custom monitors added through `Performance.add_custom_monitor()`, and [`FrameStats`]
+/// of the bridge.
+///
+/// While shown, the overlay keeps requesting repaint to sample every frame. While hidden,
+/// the bridge wakes up for the toggle action by itself.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::{*, widgets::PerformanceMonitor};
+/// # let bridge = EguiBridge::new_alloc();
+/// let monitor = PerformanceMonitor::new().toggle_action("toggle_performance");
+///
+/// bridge
+///     .bind()
+///     .register_render_callback_last(0, monitor.into_overlay())
+///     .detach();
+/// ```
+pub struct PerformanceMonitor {
+    series: Vec<Series>,
+
+    /// Number of samples kept for each series.
+    history: usize,
+
+    toggle_action: StringName,
+    visible: bool,
+}
+
+struct Series {
+    name: String,
+    unit: &'static str,
+    source: Source,
+    samples: VecDeque<f64>,
+}
+
+enum Source {
+    /// Engine monitor, multiplied by given scale.
+    Engine(Monitor, f64),
+    Custom(StringName),
+    Bridge(fn(&FrameStats) -> f64),
+}
+
+impl Default for PerformanceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerformanceMonitor {
+    pub fn new() -> Self {
+        const MS: f64 = 1000.;
+        const MIB: f64 = 1. / (1024. * 1024.);
+
+        let engine = [
+            ("FPS", "", Monitor::TIME_FPS, 1.),
+            ("Process", "ms", Monitor::TIME_PROCESS, MS),
+            ("Physics", "ms", Monitor::TIME_PHYSICS_PROCESS, MS),
+            (
+                "Draw Calls",
+                "",
+                Monitor::RENDER_TOTAL_DRAW_CALLS_IN_FRAME,
+                1.,
+            ),
+            ("Objects", "", Monitor::OBJECT_COUNT, 1.),
+            ("Nodes", "", Monitor::OBJECT_NODE_COUNT, 1.),
+            ("Video Memory", "MiB", Monitor::RENDER_VIDEO_MEM_USED, MIB),
+        ]
+        .map(|(name, unit, monitor, scale)| (name, unit, Source::Engine(monitor, scale)));
+
+        let bridge = [
+            (
+                "egui Build",
+                "ms",
+                Source::Bridge(|x| x.build_time.as_secs_f64() * MS),
+            ),
+            (
+                "egui Tessellation",
+                "ms",
+                Source::Bridge(|x| x.tessellation_time.as_secs_f64() * MS),
+            ),
+            (
+                "egui Canvas Items",
+                "",
+                Source::Bridge(|x| x.canvas_items as f64),
+            ),
+        ];
+
+        let series = engine
+            .into_iter()
+            .chain(bridge)
+            .map(|(name, unit, source)| Series {
+                name: name.into(),
+                unit,
+                source,
+                samples: VecDeque::new(),
+            })
+            .collect();
+
+        Self {
+            series,
+            history: 240,
+            toggle_action: StringName::default(),
+            visible: true,
+        }
+    }
+
+    /// Number of samples kept for each plot.
+    pub fn history(mut self, history: usize) -> Self {
+        self.history = history.max(2);
+        self
+    }
+
+    /// Input action which toggles the overlay. Empty string disables the toggle.
+    pub fn toggle_action(mut self, action: impl Into<StringName>) -> Self {
+        self.toggle_action = action.into();
+        self
+    }
+
+    /// Initial visibility of the overlay.
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Wrap this monitor into an overlay window, which can be registered with
+    /// [`crate::EguiBridge::register_render_callback_last`].
+    pub fn into_overlay(mut self) -> impl FnMut(&egui::Context) + 'static {
+        move |ctx| {
+            self.poll_toggle(ctx);
+
+            if !self.visible {
+                return;
+            }
+
+            self.sample(ctx);
+
+            egui::Window::new("Performance")
+                .id(egui::Id::new("%%PerformanceMonitor%%Overlay"))
+                .default_pos([8., 8.])
+                .default_width(260.)
+                .show(ctx, |ui| self.show(ui));
+
+            // Keep sampling, even in reactive mode.
+            ctx.request_repaint();
+        }
+    }
+
+    /// Record a sample of every monitor. Call this once per frame, if the plots are drawn
+    /// with [`Self::show`] instead of the overlay.
+    pub fn sample(&mut self, ctx: &egui::Context) {
+        self.sync_custom_monitors();
+
+        let performance = classes::Performance::singleton();
+        let stats = FrameStats::load(ctx).unwrap_or_default();
+
+        for series in &mut self.series {
+            let value = match &series.source {
+                Source::Engine(monitor, scale) => performance.get_monitor(*monitor) * scale,
+                Source::Custom(id) => {
+                    // Counters are usually reported as integers.
+                    let value = performance.get_custom_monitor(id);
+                    value
+                        .try_to::<f64>()
+                        .or_else(|_| value.try_to::<i64>().map(|x| x as f64))
+                        .unwrap_or_default()
+                }
+                Source::Bridge(get) => get(&stats),
+            };
+
+            series.samples.push_back(value);
+
+            while series.samples.len() > self.history {
+                series.samples.pop_front();
+            }
+        }
+    }
+
+    /// Draw plots of every monitor.
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for series in &self.series {
+                let latest = series.samples.back().copied().unwrap_or_default();
+                ui.label(format!("{}: {latest:.2} {}", series.name, series.unit));
+
+                let points = series
+                    .samples
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| [index as f64, *value])
+                    .collect::<Vec<_>>();
+
+                Plot::new(("%%PerformanceMonitor%%Plot", &series.name))
+                    .height(40.)
+                    .show_axes([false, true])
+                    .show_x(false)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .allow_boxed_zoom(false)
+                    .include_x(0.)
+                    .include_x(self.history as f64)
+                    .include_y(0.)
+                    .show(ui, |plot| plot.line(Line::new(PlotPoints::new(points))));
+            }
+        });
+    }
+
+    fn poll_toggle(&mut self, ctx: &egui::Context) {
+        let action = &self.toggle_action;
+
+        if action.is_empty() {
+            return;
+        }
+
+        // Hidden overlay doesn't request repaints; the bridge should poll the action.
+        WakeActions::insert(ctx, action);
+
+        if !classes::InputMap::singleton().has_action(action) {
+            return;
+        }
+
+        // Don't toggle while typing into egui.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        if classes::Input::singleton().is_action_just_pressed(action) {
+            self.visible = !self.visible;
+        }
+    }
+
+    /// Custom monitors can be added or removed at any time.
+    fn sync_custom_monitors(&mut self) {
+        let names = classes::Performance::singleton().get_custom_monitor_names();
+
+        self.series.retain(|x| match &x.source {
+            Source::Custom(id) => names.contains(id),
+            _ => true,
+        });
+
+        for id in names.iter_shared() {
+            let exists = self
+                .series
+                .iter()
+                .any(|x| matches!(&x.source, Source::Custom(x) if *x == id));
+
+            if !exists {
+                self.series.push(Series {
+                    name: id.to_string(),
+                    unit: "",
+                    source: Source::Custom(id),
+                    samples: VecDeque::new(),
+                });
+            }
+        }
+    }
+}
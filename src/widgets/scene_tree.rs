@@ -0,0 +1,364 @@
+//! Browser of the running scene tree.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use egui::collapsing_header::CollapsingState;
+use godot::{
+    classes::{self, image::Format, CanvasItem, Control, Node3D, SceneTree},
+    prelude::*,
+};
+
+use crate::helpers::ToCounterpart;
+
+/// Maximum number of nodes listed while filtering.
+const MAX_FILTER_MATCHES: usize = 500;
+
+/// Matches of the filter are collected again after this, to follow changes of the tree.
+const FILTER_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Widget which browses the scene tree of `get_tree().root`. Children of a node are
+/// walked only when the node is expanded. While filtering, the whole tree is walked only
+/// when the filter is edited, and then periodically.
+///
+/// The selected node is highlighted in the game view; `CanvasItem` with its bounds or
+/// origin, `Node3D` with its position projected by the current camera.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::{*, widgets::SceneTreeInspector};
+/// # let bridge = EguiBridge::new_alloc();
+/// let inspector = SceneTreeInspector::new().on_select(|node: Gd<Node>| {
+///     godot_print!("Selected: {}", node.get_path());
+/// });
+///
+/// bridge
+///     .bind()
+///     .register_render_callback_last(0, inspector.into_window("Scene Tree"))
+///     .detach();
+/// ```
+pub struct SceneTreeInspector {
+    filter: String,
+    selected: Option<InstanceId>,
+    highlight: bool,
+
+    /// Class icons, loaded on first use. `None` if the class has no icon.
+    icons: HashMap<String, Option<egui::TextureHandle>>,
+
+    /// Nodes which matched the filter, the filter they were collected with, and when.
+    matches: Option<(String, Instant, Vec<Gd<Node>>)>,
+
+    on_select: Option<Box<dyn FnMut(Gd<Node>)>>,
+}
+
+impl Default for SceneTreeInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneTreeInspector {
+    pub fn new() -> Self {
+        Self {
+            filter: String::new(),
+            selected: None,
+            highlight: true,
+            icons: HashMap::new(),
+            matches: None,
+            on_select: None,
+        }
+    }
+
+    /// Called whenever user selects a node.
+    pub fn on_select(mut self, on_select: impl FnMut(Gd<Node>) + 'static) -> Self {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// Whether to highlight the selected node in the game view. Enabled by default.
+    pub fn highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Currently selected node, if it's still alive.
+    pub fn selected(&self) -> Option<Gd<Node>> {
+        self.selected
+            .and_then(|id| Gd::try_from_instance_id(id).ok())
+    }
+
+    /// Select given node programmatically. This doesn't invoke the `on_select` callback.
+    pub fn select(&mut self, node: Option<&Gd<Node>>) {
+        self.selected = node.map(|x| x.instance_id());
+    }
+
+    /// Wrap this inspector into a window, which can be registered with
+    /// [`crate::EguiBridge::register_render_callback_last`].
+    pub fn into_window(
+        mut self,
+        title: impl Into<egui::WidgetText>,
+    ) -> impl FnMut(&egui::Context) + 'static {
+        let title = title.into();
+
+        move |ctx| {
+            egui::Window::new(title.clone())
+                .default_size([280., 400.])
+                .show(ctx, |ui| {
+                    self.show(ui);
+                });
+        }
+    }
+
+    /// Draw the inspector. Returns the node which is selected by user in this frame.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<Gd<Node>> {
+        let Some(root) = scene_root() else {
+            ui.weak("Scene tree is not available.");
+            return None;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.text_edit_singleline(&mut self.filter);
+        });
+
+        ui.separator();
+
+        let mut picked = None;
+
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                if self.filter.is_empty() {
+                    self.show_node(ui, root, &mut picked);
+                } else {
+                    self.show_matches(ui, root, &mut picked);
+                }
+            });
+
+        if let Some(node) = &picked {
+            self.selected = Some(node.instance_id());
+
+            if let Some(on_select) = &mut self.on_select {
+                on_select(node.clone());
+            }
+        }
+
+        if self.highlight && ui.ctx().viewport_id() == egui::ViewportId::ROOT {
+            if let Some(node) = self.selected() {
+                paint_highlight(ui.ctx(), node);
+            }
+        }
+
+        picked
+    }
+
+    fn show_node(&mut self, ui: &mut egui::Ui, node: Gd<Node>, picked: &mut Option<Gd<Node>>) {
+        if node.get_child_count() == 0 {
+            ui.horizontal(|ui| {
+                // Align with the rows which have the collapse button.
+                ui.add_space(ui.spacing().indent);
+                self.show_row(ui, &node, picked);
+            });
+
+            return;
+        }
+
+        let id = ui.make_persistent_id(("%%SceneTreeInspector%%Node", node.instance_id()));
+        let is_root = node.get_parent().is_none();
+
+        CollapsingState::load_with_default_open(ui.ctx(), id, is_root)
+            .show_header(ui, |ui| self.show_row(ui, &node, picked))
+            .body(|ui| {
+                // Children are only walked while expanded.
+                for child in node.get_children().iter_shared() {
+                    self.show_node(ui, child, picked);
+                }
+            });
+    }
+
+    fn show_matches(&mut self, ui: &mut egui::Ui, root: Gd<Node>, picked: &mut Option<Gd<Node>>) {
+        let filter = self.filter.to_lowercase();
+        let stale = self.matches.as_ref().map_or(true, |(prev, at, _)| {
+            *prev != filter || at.elapsed() >= FILTER_REFRESH_INTERVAL
+        });
+
+        if stale {
+            let mut matches = Vec::new();
+            collect_matches(root, &filter, &mut matches);
+            self.matches = Some((filter, Instant::now(), matches));
+        }
+
+        let matches = self
+            .matches
+            .as_ref()
+            .map(|(.., x)| x.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| x.is_instance_valid())
+            .collect::<Vec<_>>();
+
+        if matches.is_empty() {
+            ui.weak("No matching node.");
+            return;
+        }
+
+        for node in &matches {
+            ui.horizontal(|ui| self.show_row(ui, node, picked))
+                .response
+                .on_hover_text(node.get_path().to_string());
+        }
+
+        if matches.len() >= MAX_FILTER_MATCHES {
+            ui.weak(format!("Showing first {MAX_FILTER_MATCHES} matches."));
+        }
+    }
+
+    fn show_row(&mut self, ui: &mut egui::Ui, node: &Gd<Node>, picked: &mut Option<Gd<Node>>) {
+        let class = node.get_class().to_string();
+
+        if let Some(icon) = self.icon(ui.ctx(), &class) {
+            ui.image((icon, egui::vec2(16., 16.)));
+        }
+
+        let selected = self.selected == Some(node.instance_id());
+        let response = ui
+            .selectable_label(selected, node.get_name().to_string())
+            .on_hover_text(class.as_str());
+
+        if response.clicked() {
+            *picked = Some(node.clone());
+        }
+    }
+
+    fn icon(&mut self, ctx: &egui::Context, class: &str) -> Option<egui::TextureId> {
+        self.icons
+            .entry(class.to_owned())
+            .or_insert_with(|| load_class_icon(ctx, class))
+            .as_ref()
+            .map(|x| x.id())
+    }
+}
+
+fn scene_root() -> Option<Gd<Node>> {
+    let tree = classes::Engine::singleton()
+        .get_main_loop()?
+        .try_cast::<SceneTree>()
+        .ok()?;
+
+    tree.get_root().map(|x| x.upcast())
+}
+
+fn collect_matches(node: Gd<Node>, filter: &str, out: &mut Vec<Gd<Node>>) {
+    if out.len() >= MAX_FILTER_MATCHES {
+        return;
+    }
+
+    let name = node.get_name().to_string().to_lowercase();
+    let class = node.get_class().to_string().to_lowercase();
+
+    if name.contains(filter) || class.contains(filter) {
+        out.push(node.clone());
+    }
+
+    for child in node.get_children().iter_shared() {
+        collect_matches(child, filter, out);
+    }
+}
+
+/// Class icons are part of the editor theme; there's no icon outside of the editor.
+fn load_class_icon(ctx: &egui::Context, class: &str) -> Option<egui::TextureHandle> {
+    if !classes::Engine::singleton().is_editor_hint() {
+        return None;
+    }
+
+    let theme = classes::EditorInterface::singleton().get_editor_theme()?;
+    if !theme.has_icon(class, "EditorIcons") {
+        return None;
+    }
+
+    let mut image = theme.get_icon(class, "EditorIcons")?.get_image()?;
+    image.convert(Format::RGBA8);
+
+    let size = [image.get_width() as usize, image.get_height() as usize];
+    let pixels = egui::ColorImage::from_rgba_unmultiplied(size, image.get_data().as_slice());
+
+    Some(ctx.load_texture(
+        format!("godot-class-icon/{class}"),
+        pixels,
+        Default::default(),
+    ))
+}
+
+/// Outline the node in the game view. This assumes the root viewport covers the game
+/// view; nodes inside of `SubViewport` are not placed correctly.
+fn paint_highlight(ctx: &egui::Context, node: Gd<Node>) {
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("%%SceneTreeInspector%%Highlight"),
+    ));
+
+    let stroke = egui::Stroke::new(2., egui::Color32::YELLOW);
+    let ppp = ctx.pixels_per_point();
+
+    let node = match node.try_cast::<Control>() {
+        Ok(control) => {
+            if !control.is_visible_in_tree() {
+                return;
+            }
+
+            let xform = control.get_global_transform_with_canvas();
+            let size = control.get_size();
+            let points = [
+                Vector2::ZERO,
+                Vector2::new(size.x, 0.),
+                size,
+                Vector2::new(0., size.y),
+            ]
+            .map(|x| (xform * x / ppp).to_alternative());
+
+            painter.add(egui::Shape::closed_line(points.to_vec(), stroke));
+            return;
+        }
+        Err(node) => node,
+    };
+
+    let position = match node.try_cast::<CanvasItem>() {
+        Ok(item) => {
+            if !item.is_visible_in_tree() {
+                return;
+            }
+
+            item.get_global_transform_with_canvas().origin
+        }
+        Err(node) => {
+            let Ok(spatial) = node.try_cast::<Node3D>() else {
+                // Nothing to show.
+                return;
+            };
+
+            let Some(camera) = spatial.get_viewport().and_then(|x| x.get_camera_3d()) else {
+                return;
+            };
+
+            let position = spatial.get_global_position();
+            if camera.is_position_behind(position) {
+                return;
+            }
+
+            camera.unproject_position(position)
+        }
+    };
+
+    let center = (position / ppp).to_alternative();
+    painter.circle_stroke(center, 8., stroke);
+    painter.line_segment(
+        [center - egui::vec2(12., 0.), center + egui::vec2(12., 0.)],
+        stroke,
+    );
+    painter.line_segment(
+        [center - egui::vec2(0., 12.), center + egui::vec2(0., 12.)],
+        stroke,
+    );
+}
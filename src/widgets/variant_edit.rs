@@ -0,0 +1,576 @@
+//! Egui widgets to display and edit Godot variants.
+
+use std::hash::Hash;
+
+use egui::emath::Numeric;
+use godot::{builtin::real, meta::ArrayElement, prelude::*};
+
+/// Containers nested deeper than this are not expanded; they may contain themselves.
+const MAX_DEPTH: usize = 16;
+
+/// Widget which edits a [`Variant`] of any type. Containers are drawn as collapsibles,
+/// and long containers are paginated.
+///
+/// Arrays and dictionaries are replaced with edited copies once changed, rather than
+/// modified in place. Typed arrays keep their element type. Object references, `Rid`,
+/// `Callable`, `Signal` and typed arrays of objects are read-only.
+///
+/// ```no_run
+/// # use godot::prelude::*;
+/// # use gdext_egui::widgets::VariantEdit;
+/// # fn show(ui: &mut egui::Ui, value: &mut Variant) {
+/// if ui.add(VariantEdit::new(value)).changed() {
+///     godot_print!("New value: {value}");
+/// }
+/// # }
+/// ```
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+pub struct VariantEdit<'a> {
+    value: &'a mut Variant,
+    id_source: Option<egui::Id>,
+    read_only: bool,
+    page_size: usize,
+}
+
+impl<'a> VariantEdit<'a> {
+    pub fn new(value: &'a mut Variant) -> Self {
+        Self {
+            value,
+            id_source: None,
+            read_only: false,
+            page_size: 32,
+        }
+    }
+
+    /// Identifies the state of the widget, e.g. collapsed or current page. Required if
+    /// the widget moves around in the ui.
+    pub fn id_source(mut self, id_source: impl Hash) -> Self {
+        self.id_source = Some(egui::Id::new(id_source));
+        self
+    }
+
+    /// Only display the value. Containers can still be expanded.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Number of container items shown in a page.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+}
+
+impl egui::Widget for VariantEdit<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let id = self.id_source.unwrap_or_else(|| ui.next_auto_id());
+        let editor = Editor {
+            read_only: self.read_only,
+            page_size: self.page_size,
+        };
+
+        let value = self.value;
+        let egui::InnerResponse {
+            inner: changed,
+            mut response,
+        } = ui.push_id(id, |ui| editor.edit(ui, value, 0));
+
+        if changed {
+            response.mark_changed();
+        }
+
+        response
+    }
+}
+
+/* ------------------------------------------ Internals ----------------------------------------- */
+
+struct Editor {
+    read_only: bool,
+    page_size: usize,
+}
+
+macro_rules! edit_packed {
+    ($self:ident, $ui:ident, $value:ident, $depth:ident, $ty:ty) => {{
+        let mut packed = $value.to::<$ty>();
+        let title = format!("{} ({})", stringify!($ty), packed.len());
+        let changed = $self.edit_slice($ui, title, packed.as_mut_slice(), $depth);
+
+        if changed {
+            *$value = packed.to_variant();
+        }
+
+        changed
+    }};
+}
+
+/// Edit a typed array through its element type, trying given types in order. Returns
+/// from the enclosing function if the array is of any of them.
+macro_rules! edit_typed_array {
+    ($self:ident, $ui:ident, $value:ident, $depth:ident, $($name:literal => $ty:ty),* $(,)?) => {
+        $(
+            if let Some(changed) = $self.edit_typed_array::<$ty>($ui, $name, $value, $depth) {
+                return changed;
+            }
+        )*
+    };
+}
+
+impl Editor {
+    /// Returns true if the value was changed.
+    fn edit(&self, ui: &mut egui::Ui, value: &mut Variant, depth: usize) -> bool {
+        match value.get_type() {
+            VariantType::NIL => {
+                ui.weak("null");
+                false
+            }
+
+            VariantType::BOOL => {
+                self.leaf(ui, value, |ui, x: &mut bool| ui.checkbox(x, "").changed())
+            }
+
+            VariantType::INT => self.leaf(ui, value, |ui, x: &mut i64| {
+                ui.add(egui::DragValue::new(x)).changed()
+            }),
+
+            VariantType::FLOAT => self.leaf(ui, value, |ui, x: &mut f64| {
+                ui.add(egui::DragValue::new(x).speed(0.01)).changed()
+            }),
+
+            VariantType::STRING => self.leaf(ui, value, |ui, x: &mut GString| {
+                edit_text(ui, x, |x| GString::from(x.as_str()))
+            }),
+
+            VariantType::STRING_NAME => self.leaf(ui, value, |ui, x: &mut StringName| {
+                edit_text(ui, x, |x| StringName::from(x.as_str()))
+            }),
+
+            VariantType::NODE_PATH => self.leaf(ui, value, |ui, x: &mut NodePath| {
+                edit_text(ui, x, |x| NodePath::from(x.as_str()))
+            }),
+
+            VariantType::VECTOR2 => self.leaf(ui, value, |ui, x: &mut Vector2| {
+                drag_row(ui, [("x", &mut x.x), ("y", &mut x.y)])
+            }),
+
+            VariantType::VECTOR2I => self.leaf(ui, value, |ui, x: &mut Vector2i| {
+                drag_row(ui, [("x", &mut x.x), ("y", &mut x.y)])
+            }),
+
+            VariantType::VECTOR3 => self.leaf(ui, value, |ui, x: &mut Vector3| {
+                drag_row(ui, [("x", &mut x.x), ("y", &mut x.y), ("z", &mut x.z)])
+            }),
+
+            VariantType::VECTOR3I => self.leaf(ui, value, |ui, x: &mut Vector3i| {
+                drag_row(ui, [("x", &mut x.x), ("y", &mut x.y), ("z", &mut x.z)])
+            }),
+
+            VariantType::VECTOR4 => self.leaf(ui, value, |ui, x: &mut Vector4| {
+                drag_row(ui, vector4_fields(x))
+            }),
+
+            VariantType::VECTOR4I => self.leaf(ui, value, |ui, x: &mut Vector4i| {
+                let Vector4i { x, y, z, w } = x;
+                drag_row(ui, [("x", x), ("y", y), ("z", z), ("w", w)])
+            }),
+
+            VariantType::RECT2 => self.leaf(ui, value, |ui, x: &mut Rect2| {
+                let (position, size) = (&mut x.position, &mut x.size);
+                ui.vertical(|ui| {
+                    drag_row(ui, [("x", &mut position.x), ("y", &mut position.y)])
+                        | drag_row(ui, [("w", &mut size.x), ("h", &mut size.y)])
+                })
+                .inner
+            }),
+
+            VariantType::RECT2I => self.leaf(ui, value, |ui, x: &mut Rect2i| {
+                let (position, size) = (&mut x.position, &mut x.size);
+                ui.vertical(|ui| {
+                    drag_row(ui, [("x", &mut position.x), ("y", &mut position.y)])
+                        | drag_row(ui, [("w", &mut size.x), ("h", &mut size.y)])
+                })
+                .inner
+            }),
+
+            VariantType::TRANSFORM2D => self.leaf(ui, value, |ui, x: &mut Transform2D| {
+                ui.vertical(|ui| {
+                    drag_row(ui, [("ax", &mut x.a.x), ("ay", &mut x.a.y)])
+                        | drag_row(ui, [("bx", &mut x.b.x), ("by", &mut x.b.y)])
+                        | drag_row(ui, [("ox", &mut x.origin.x), ("oy", &mut x.origin.y)])
+                })
+                .inner
+            }),
+
+            VariantType::PLANE => self.leaf(ui, value, |ui, x: &mut Plane| {
+                let normal = &mut x.normal;
+                drag_row(
+                    ui,
+                    [
+                        ("x", &mut normal.x),
+                        ("y", &mut normal.y),
+                        ("z", &mut normal.z),
+                        ("d", &mut x.d),
+                    ],
+                )
+            }),
+
+            VariantType::QUATERNION => self.leaf(ui, value, |ui, x: &mut Quaternion| {
+                let Quaternion { x, y, z, w } = x;
+                drag_row(ui, [("x", x), ("y", y), ("z", z), ("w", w)])
+            }),
+
+            VariantType::AABB => self.leaf(ui, value, |ui, x: &mut Aabb| {
+                let (position, size) = (&mut x.position, &mut x.size);
+                ui.vertical(|ui| {
+                    drag_row(ui, vector3_fields(position)) | drag_row(ui, vector3_fields(size))
+                })
+                .inner
+            }),
+
+            VariantType::BASIS => self.leaf(ui, value, |ui, x: &mut Basis| edit_basis(ui, x)),
+
+            VariantType::TRANSFORM3D => self.leaf(ui, value, |ui, x: &mut Transform3D| {
+                ui.vertical(|ui| {
+                    edit_basis(ui, &mut x.basis) | drag_row(ui, vector3_fields(&mut x.origin))
+                })
+                .inner
+            }),
+
+            VariantType::PROJECTION => self.leaf(ui, value, |ui, x: &mut Projection| {
+                ui.vertical(|ui| {
+                    let mut changed = false;
+                    for col in &mut x.cols {
+                        changed |= drag_row(ui, vector4_fields(col));
+                    }
+                    changed
+                })
+                .inner
+            }),
+
+            VariantType::COLOR => self.leaf(ui, value, |ui, x: &mut Color| {
+                let mut rgba = [x.r, x.g, x.b, x.a];
+                let changed = ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed();
+                *x = Color::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+                changed
+            }),
+
+            VariantType::RID => {
+                let rid = value.to::<Rid>();
+                ui.weak(format!("RID({})", rid.to_u64()));
+                false
+            }
+
+            VariantType::OBJECT => {
+                match value.try_to::<Gd<Object>>() {
+                    Ok(object) => {
+                        ui.weak(format!("{}#{}", object.get_class(), object.instance_id()))
+                    }
+                    Err(_) => ui.weak("null"),
+                };
+
+                false
+            }
+
+            VariantType::ARRAY => {
+                let Ok(array) = value.try_to::<VariantArray>() else {
+                    // Typed arrays can't be replaced with untyped ones.
+                    edit_typed_array!(self, ui, value, depth,
+                        "bool" => bool,
+                        "int" => i64,
+                        "float" => f64,
+                        "String" => GString,
+                        "StringName" => StringName,
+                        "NodePath" => NodePath,
+                        "Vector2" => Vector2,
+                        "Vector2i" => Vector2i,
+                        "Vector3" => Vector3,
+                        "Vector3i" => Vector3i,
+                        "Vector4" => Vector4,
+                        "Vector4i" => Vector4i,
+                        "Rect2" => Rect2,
+                        "Rect2i" => Rect2i,
+                        "Color" => Color,
+                        "Dictionary" => Dictionary,
+                    );
+
+                    // Arrays of objects, or other rarely used types.
+                    ui.weak(value.stringify().to_string());
+                    return false;
+                };
+
+                let title = format!("Array ({})", array.len());
+                let mut edited = None;
+
+                self.collapsible(ui, title, depth, |ui| {
+                    let mut items = array.iter_shared().collect::<Vec<_>>();
+                    let changed = self.edit_list(ui, items.len(), |ui, index| {
+                        ui.label(index.to_string());
+                        self.edit(ui, &mut items[index], depth + 1)
+                    });
+
+                    if changed {
+                        edited = Some(items);
+                    }
+                });
+
+                let Some(items) = edited else {
+                    return false;
+                };
+
+                *value = items.into_iter().collect::<VariantArray>().to_variant();
+                true
+            }
+
+            VariantType::DICTIONARY => {
+                let dict = value.to::<Dictionary>();
+                let title = format!("Dictionary ({})", dict.len());
+                let mut edited = None;
+
+                self.collapsible(ui, title, depth, |ui| {
+                    let mut items = dict.iter_shared().collect::<Vec<_>>();
+                    let changed = self.edit_list(ui, items.len(), |ui, index| {
+                        let (key, value) = &mut items[index];
+
+                        // Keys are not editable; it may collide with other keys.
+                        ui.label(key.stringify().to_string());
+                        self.edit(ui, value, depth + 1)
+                    });
+
+                    if changed {
+                        edited = Some(items);
+                    }
+                });
+
+                let Some(items) = edited else {
+                    return false;
+                };
+
+                *value = items.into_iter().collect::<Dictionary>().to_variant();
+                true
+            }
+
+            VariantType::PACKED_BYTE_ARRAY => edit_packed!(self, ui, value, depth, PackedByteArray),
+            VariantType::PACKED_INT32_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedInt32Array)
+            }
+            VariantType::PACKED_INT64_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedInt64Array)
+            }
+            VariantType::PACKED_FLOAT32_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedFloat32Array)
+            }
+            VariantType::PACKED_FLOAT64_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedFloat64Array)
+            }
+            VariantType::PACKED_STRING_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedStringArray)
+            }
+            VariantType::PACKED_VECTOR2_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedVector2Array)
+            }
+            VariantType::PACKED_VECTOR3_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedVector3Array)
+            }
+            VariantType::PACKED_COLOR_ARRAY => {
+                edit_packed!(self, ui, value, depth, PackedColorArray)
+            }
+
+            // Callable, Signal, and whatever else; not editable.
+            _ => {
+                ui.weak(value.stringify().to_string());
+                false
+            }
+        }
+    }
+
+    /// Edit a value converted to `T`. The value is written back only if changed.
+    fn leaf<T: FromGodot + ToGodot>(
+        &self,
+        ui: &mut egui::Ui,
+        value: &mut Variant,
+        edit: impl FnOnce(&mut egui::Ui, &mut T) -> bool,
+    ) -> bool {
+        let mut x = value.to::<T>();
+        let changed = ui
+            .add_enabled_ui(!self.read_only, |ui| edit(ui, &mut x))
+            .inner;
+
+        if changed {
+            *value = x.to_variant();
+        }
+
+        changed
+    }
+
+    fn edit_slice<T: FromGodot + ToGodot>(
+        &self,
+        ui: &mut egui::Ui,
+        title: String,
+        items: &mut [T],
+        depth: usize,
+    ) -> bool {
+        self.collapsible(ui, title, depth, |ui| {
+            self.edit_list(ui, items.len(), |ui, index| {
+                ui.label(index.to_string());
+
+                let mut item = items[index].to_variant();
+                if !self.edit(ui, &mut item, depth + 1) {
+                    return false;
+                }
+
+                // Element type never changes; the conversion is just for type safety.
+                match item.try_to::<T>() {
+                    Ok(x) => {
+                        items[index] = x;
+                        true
+                    }
+                    Err(_) => false,
+                }
+            })
+        })
+    }
+
+    /// Returns `None` if the value is not an array of `T`.
+    fn edit_typed_array<T: ArrayElement + FromGodot + ToGodot>(
+        &self,
+        ui: &mut egui::Ui,
+        type_name: &str,
+        value: &mut Variant,
+        depth: usize,
+    ) -> Option<bool> {
+        let array = value.try_to::<Array<T>>().ok()?;
+        let mut items = array.iter_shared().collect::<Vec<_>>();
+
+        let title = format!("Array[{type_name}] ({})", items.len());
+        let changed = self.edit_slice(ui, title, &mut items, depth);
+
+        if changed {
+            *value = items.into_iter().collect::<Array<T>>().to_variant();
+        }
+
+        Some(changed)
+    }
+
+    /// Collapsible header of containers. Returns the result of `body` if it's expanded.
+    ///
+    /// Every value is drawn in its own id scope (e.g. rows of lists), which holds a single
+    /// header at most; the header id is stable as long as the scope is.
+    fn collapsible(
+        &self,
+        ui: &mut egui::Ui,
+        title: String,
+        depth: usize,
+        body: impl FnOnce(&mut egui::Ui) -> bool,
+    ) -> bool {
+        if depth >= MAX_DEPTH {
+            ui.weak(title);
+            return false;
+        }
+
+        egui::CollapsingHeader::new(title)
+            .id_source(ui.id().with("%%VariantEdit%%Header"))
+            .show(ui, body)
+            .body_returned
+            .unwrap_or(false)
+    }
+
+    /// Draw a page of `len` items. `item` draws a row of given index, which has a label and
+    /// an editor.
+    fn edit_list(
+        &self,
+        ui: &mut egui::Ui,
+        len: usize,
+        mut item: impl FnMut(&mut egui::Ui, usize) -> bool,
+    ) -> bool {
+        let id = ui.id().with("%%VariantEdit%%Page");
+        let pages = len.div_ceil(self.page_size).max(1);
+        let mut page = ui
+            .data(|x| x.get_temp::<usize>(id))
+            .unwrap_or(0)
+            .min(pages - 1);
+
+        if pages > 1 {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(page > 0, egui::Button::new("<")).clicked() {
+                    page -= 1;
+                }
+
+                ui.label(format!("{} / {pages}", page + 1));
+
+                if ui
+                    .add_enabled(page + 1 < pages, egui::Button::new(">"))
+                    .clicked()
+                {
+                    page += 1;
+                }
+            });
+
+            ui.data_mut(|x| x.insert_temp(id, page));
+        }
+
+        let range = page * self.page_size..len.min((page + 1) * self.page_size);
+        let mut changed = false;
+
+        egui::Grid::new(id.with("Grid"))
+            .num_columns(2)
+            .show(ui, |ui| {
+                for index in range {
+                    ui.push_id(index, |ui| changed |= item(ui, index));
+                    ui.end_row();
+                }
+            });
+
+        changed
+    }
+}
+
+fn edit_text<T: ToString>(ui: &mut egui::Ui, value: &mut T, from: impl Fn(String) -> T) -> bool {
+    let mut text = value.to_string();
+    let changed = ui.text_edit_singleline(&mut text).changed();
+
+    if changed {
+        *value = from(text);
+    }
+
+    changed
+}
+
+fn edit_basis(ui: &mut egui::Ui, basis: &mut Basis) -> bool {
+    ui.vertical(|ui| {
+        let mut changed = false;
+        for row in &mut basis.rows {
+            changed |= drag_row(ui, vector3_fields(row));
+        }
+        changed
+    })
+    .inner
+}
+
+fn vector3_fields(x: &mut Vector3) -> [(&'static str, &mut real); 3] {
+    let Vector3 { x, y, z } = x;
+    [("x", x), ("y", y), ("z", z)]
+}
+
+fn vector4_fields(x: &mut Vector4) -> [(&'static str, &mut real); 4] {
+    let Vector4 { x, y, z, w } = x;
+    [("x", x), ("y", y), ("z", z), ("w", w)]
+}
+
+/// Row of drag values, each prefixed with its label.
+fn drag_row<T: Numeric, const N: usize>(ui: &mut egui::Ui, fields: [(&str, &mut T); N]) -> bool {
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        for (label, value) in fields {
+            changed |= ui
+                .add(
+                    egui::DragValue::new(value)
+                        .prefix(format!("{label}: "))
+                        .speed(0.1),
+                )
+                .changed();
+        }
+        changed
+    })
+    .inner
+}